@@ -0,0 +1,86 @@
+//! Shared constants: DynamoDB table names and other fixed configuration.
+
+/// Buyer accounts table.
+pub const BUYER_TABLE: &str = "Buyers";
+/// Seller accounts table.
+pub const SELLER_TABLE: &str = "Sellers";
+/// Admin accounts table.
+pub const ADMIN_TABLE: &str = "Admins";
+/// Auction item table.
+pub const ITEM_TABLE: &str = "Items";
+/// Bid table.
+pub const BID_TABLE: &str = "Bids";
+/// Completed purchase table.
+pub const PURCHASE_TABLE: &str = "Purchases";
+/// Background task queue table.
+pub const TASK_TABLE: &str = "Tasks";
+/// Minimum amount a new leading bid must clear the previous one by.
+pub const MIN_BID_INCREMENT: u64 = 1;
+/// Retries `buyer_place_bid` attempts against a fresh read before giving up
+/// with a conflict when `bidVersion` keeps getting raced by other bidders.
+pub const PLACE_BID_MAX_RETRIES: u32 = 5;
+/// S3 bucket item images are stored in.
+pub const IMAGE_BUCKET: &str = "auction-house-item-images";
+/// How long a presigned image URL stays valid, in seconds.
+pub const IMAGE_PRESIGN_EXPIRY_SECS: u64 = 900;
+/// Dump/restore job status table.
+pub const DUMP_TABLE: &str = "Dumps";
+/// S3 bucket portable backup archives are written to and read from.
+pub const DUMP_BUCKET: &str = "auction-house-dumps";
+/// Schema version tagged onto every record in a dump archive.
+pub const DUMP_SCHEMA_VERSION: u32 = 1;
+/// Default page size for cursor-paginated listing endpoints.
+pub const DEFAULT_PAGE_LIMIT: u32 = 20;
+/// SIWE login nonce table, keyed by wallet address with a DynamoDB TTL on `expireAt`.
+pub const NONCE_TABLE: &str = "WalletNonces";
+/// How long a minted SIWE nonce stays valid, in seconds.
+pub const NONCE_TTL_SECS: u64 = 300;
+/// GSI on `Buyers` keyed by `walletAddress`, used to find wallet-login buyers.
+pub const WALLET_ADDRESS_INDEX: &str = "walletAddressIndex";
+/// Table holding in-flight OPAQUE `ServerLogin` state between login start/finish.
+pub const OPAQUE_LOGIN_STATE_TABLE: &str = "OpaqueLoginState";
+/// How long an OPAQUE login round trip has to complete, in seconds.
+pub const OPAQUE_LOGIN_STATE_TTL_SECS: u64 = 300;
+/// Server-side record of every minted JWT, keyed by `jti`, enabling revocation.
+pub const ACCESS_TOKEN_TABLE: &str = "AccessTokens";
+/// GSI on `AccessTokens` keyed by `userId`, used to revoke every token for a user at once.
+pub const ACCESS_TOKEN_USER_INDEX: &str = "userIdIndex";
+/// How long a token-id validity verdict is cached in-memory before re-checking DynamoDB.
+pub const ACCESS_TOKEN_CACHE_TTL_SECS: u64 = 60;
+/// Largest request body accepted by the item image upload endpoint, in bytes.
+pub const IMAGE_UPLOAD_MAX_BYTES: usize = 10 * 1024 * 1024;
+/// Longest edge, in pixels, a generated image thumbnail is downscaled to.
+pub const IMAGE_THUMBNAIL_MAX_EDGE: u32 = 320;
+/// Most images a single item may have, counting both already-stored images
+/// and ones a presign request is about to mint upload URLs for.
+pub const IMAGE_MAX_COUNT_PER_ITEM: usize = 10;
+/// Default interval between auction-settlement sweeps, in seconds, used when
+/// `AUCTION_SETTLEMENT_INTERVAL_SECS` isn't set. See `task::run_settlement_sweep`.
+pub const DEFAULT_AUCTION_SETTLEMENT_INTERVAL_SECS: u64 = 30;
+/// Default interval between JWKS document refreshes, in seconds, used when
+/// `JWKS_REFRESH_INTERVAL_SECS` isn't set. See `jwks::spawn_jwks_refresh`.
+pub const DEFAULT_JWKS_REFRESH_INTERVAL_SECS: u64 = 300;
+/// Table holding pending email-verification tokens, keyed by the token itself.
+pub const VERIFICATION_TABLE: &str = "EmailVerifications";
+/// How long an issued verification token stays valid, in seconds.
+pub const VERIFICATION_TOKEN_TTL_SECS: u64 = 24 * 60 * 60;
+/// GSI on `EmailVerifications` keyed by `userId`, used to look up a user's
+/// outstanding verification token (e.g. to resend or, in tests, to activate
+/// an account without a real mailbox).
+pub const VERIFICATION_USER_INDEX: &str = "userIdIndex";
+/// Table holding single-use password-reset tokens, keyed by the token itself.
+pub const PASSWORD_RESET_TABLE: &str = "PasswordResets";
+/// How long an issued password-reset token stays valid, in seconds.
+pub const PASSWORD_RESET_TOKEN_TTL_SECS: u64 = 30 * 60;
+/// Table holding refresh-token sessions, keyed by session id. The session id
+/// doubles as the opaque refresh token handed to the client.
+pub const SESSION_TABLE: &str = "Sessions";
+/// GSI on `Sessions` keyed by `userId`, used to list or revoke a user's sessions.
+pub const SESSION_USER_INDEX: &str = "userIdIndex";
+/// How long a refresh token stays valid, in seconds.
+pub const SESSION_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+/// Table holding in-flight OAuth2 authorization attempts (PKCE verifier and
+/// linked `userType`), keyed by the CSRF state value itself.
+pub const OAUTH_STATE_TABLE: &str = "OAuthState";
+/// How long an OAuth2 authorization attempt has to complete, in seconds.
+pub const OAUTH_STATE_TTL_SECS: u64 = 10 * 60;
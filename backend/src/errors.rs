@@ -2,10 +2,16 @@ use aws_sdk_dynamodb::{
     error::SdkError as DynamoSdkError,
     operation::{
         delete_item::DeleteItemError, get_item::GetItemError, put_item::PutItemError,
-        query::QueryError, transact_write_items::TransactWriteItemsError,
+        query::QueryError, scan::ScanError, transact_write_items::TransactWriteItemsError,
         update_item::UpdateItemError,
     },
 };
+use aws_sdk_s3::{
+    error::SdkError as S3SdkError,
+    operation::{get_object::GetObjectError, put_object::PutObjectError},
+    presigning::PresigningConfigError,
+    primitives::ByteStreamError,
+};
 use axum::{
     http::{self, StatusCode},
     response::{IntoResponse, Response},
@@ -45,27 +51,73 @@ pub enum HandlerError {
     DynamoDBUpdateError(#[from] DynamoSdkError<UpdateItemError>),
     #[error("DynamoDB Error: TransactWriteItems: {0}")]
     DynamoDBTransactWriteItemsError(#[from] DynamoSdkError<TransactWriteItemsError>),
+    #[error("DynamoDB Error: TransactWriteItems (chunk {chunk_index}): {source}")]
+    TransactChunkError {
+        /// Index, among the chunks `transact_chunked` split the transaction into, that failed.
+        chunk_index: usize,
+        #[source]
+        source: DynamoSdkError<TransactWriteItemsError>,
+    },
+    #[error("DynamoDB Error: Scan: {0}")]
+    DynamoDBScanError(#[from] DynamoSdkError<ScanError>),
     #[error("Failed to build transaction: {0}")]
     TransactionBuildError(#[from] aws_sdk_dynamodb::error::BuildError),
     #[error("JWT operation failed: {0}")]
     JWTError(#[from] jsonwebtoken::errors::Error),
-    #[error("PasswordHash error: {0}")]
-    PasswordHashError(#[from] scrypt::password_hash::Error),
     #[error("SerdeDynamo failed to process DynamoDB data: {0}")]
     SerdeDynamoError(#[from] serde_dynamo::Error),
     #[error("HTTP library error: {0}")]
     HttpError(#[from] http::Error),
+    #[error("S3 Error: PutObject: {0}")]
+    S3PutObjectError(#[from] S3SdkError<PutObjectError>),
+    #[error("S3 Error: GetObject: {0}")]
+    S3GetObjectError(#[from] S3SdkError<GetObjectError>),
+    #[error("Failed to build presigned URL: {0}")]
+    PresigningConfigError(#[from] PresigningConfigError),
+    #[error("Failed to read S3 object body: {0}")]
+    ByteStreamError(#[from] ByteStreamError),
+    #[error("Image processing error: {0}")]
+    ImageError(#[from] image::ImageError),
+    #[error("JSON serialization error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("SIWE error: {0}")]
+    SiweError(#[from] crate::models::siwe::SiweError),
+    #[error("OPAQUE protocol error: {0}")]
+    OpaqueError(#[from] opaque_ke::errors::ProtocolError),
+    #[error("Failed to build SES email content: {0}")]
+    SesBuildError(#[from] aws_sdk_sesv2::error::BuildError),
+    #[error("SES Error: SendEmail: {0}")]
+    SesSendEmailError(
+        #[from] aws_sdk_sesv2::error::SdkError<aws_sdk_sesv2::operation::send_email::SendEmailError>,
+    ),
+    #[error("Token has been revoked")]
+    TokenRevokedError,
+    #[error("Account has not been verified yet")]
+    AccountNotVerified,
+    #[error("Bid conflict: {0}")]
+    BidConflict(String),
     #[error("Handler failed with status {0}: {1}")]
     HandlerError(StatusCode, String),
+    #[error("Gateway transaction rejected: a write's condition_expression was not met")]
+    GatewayConditionCheckFailed,
+    #[error("OAuth provider request failed: {0}")]
+    OAuthHttpError(#[from] reqwest::Error),
 }
 
 impl From<HandlerError> for ErrorResponse {
     fn from(value: HandlerError) -> Self {
         Self {
-            status: if let &HandlerError::HandlerError(s, _) = &value {
-                s.as_u16()
-            } else {
-                StatusCode::INTERNAL_SERVER_ERROR.as_u16()
+            status: match &value {
+                HandlerError::HandlerError(s, _) => s.as_u16(),
+                HandlerError::SiweError(_) => StatusCode::BAD_REQUEST.as_u16(),
+                HandlerError::OpaqueError(_) => StatusCode::UNAUTHORIZED.as_u16(),
+                HandlerError::TokenRevokedError => StatusCode::UNAUTHORIZED.as_u16(),
+                HandlerError::AccountNotVerified => StatusCode::FORBIDDEN.as_u16(),
+                HandlerError::BidConflict(_) => StatusCode::CONFLICT.as_u16(),
+                HandlerError::GatewayConditionCheckFailed => StatusCode::CONFLICT.as_u16(),
+                _ => StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
             },
             inner_status: match &value {
                 HandlerError::DynamoDBGetError(e) => e.raw_response().map(|r| r.status().as_u16()),
@@ -79,6 +131,9 @@ impl From<HandlerError> for ErrorResponse {
                 HandlerError::DynamoDBUpdateError(e) => {
                     e.raw_response().map(|r| r.status().as_u16())
                 }
+                HandlerError::TransactChunkError { source, .. } => {
+                    source.raw_response().map(|r| r.status().as_u16())
+                }
                 _ => None,
             },
             message: value.to_string(),
@@ -114,3 +169,16 @@ impl HandlerError {
         Self::HandlerError(StatusCode::NOT_FOUND, "Item not found".to_string())
     }
 }
+
+/// Whether `err` is a DynamoDB `ConditionalCheckFailedException` from a
+/// plain (non-transactional) `update_item` call, i.e. an `ItemTransition`
+/// guard (see `models::item`) rejected a concurrent write.
+pub fn is_update_condition_check_failed(err: &HandlerError) -> bool {
+    matches!(
+        err,
+        HandlerError::DynamoDBUpdateError(source)
+            if source
+                .as_service_error()
+                .is_some_and(UpdateItemError::is_conditional_check_failed_exception)
+    )
+}
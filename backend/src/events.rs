@@ -0,0 +1,41 @@
+//! Sharded per-item broadcast channels backing the live bid/item SSE endpoints.
+
+use dashmap::DashMap;
+use tokio::sync::broadcast;
+
+use crate::models::{bid::BidEvent, item::ItemRef};
+
+/// How many unconsumed events a slow subscriber can fall behind by before
+/// `tokio::sync::broadcast` starts dropping the oldest ones for it.
+const BID_EVENT_CHANNEL_CAPACITY: usize = 32;
+
+/// Map of item -> live bid-event channel, created lazily on first use.
+#[derive(Default)]
+pub struct BidEventBus {
+    channels: DashMap<ItemRef, broadcast::Sender<BidEvent>>,
+}
+
+impl BidEventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publish `event` to every current subscriber of `item`, creating its
+    /// channel if needed. If nobody is currently subscribed, the event is
+    /// dropped.
+    pub fn publish(&self, item: &ItemRef, event: BidEvent) {
+        let sender = self
+            .channels
+            .entry(item.clone())
+            .or_insert_with(|| broadcast::channel(BID_EVENT_CHANNEL_CAPACITY).0);
+        let _ = sender.send(event);
+    }
+
+    /// Subscribe to live events for `item`, creating its channel if needed.
+    pub fn subscribe(&self, item: &ItemRef) -> broadcast::Receiver<BidEvent> {
+        self.channels
+            .entry(item.clone())
+            .or_insert_with(|| broadcast::channel(BID_EVENT_CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+}
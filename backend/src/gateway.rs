@@ -0,0 +1,477 @@
+//! Storage abstraction decoupling route handlers from the DynamoDB SDK.
+//!
+//! Handlers used to call `Client::new(&state.aws_config)` directly and
+//! inline their own `AttributeValue` plumbing, which meant a multi-entity
+//! commit like `seller::seller_fulfill_item_by_id`'s fund transfer /
+//! purchase-record / item-archive / bid-deactivate flow could only be
+//! exercised against live AWS. [`Gateway`] gives handlers a narrow,
+//! SDK-agnostic surface backed by either [`DynamoGateway`] (production) or
+//! [`InMemoryGateway`] (tests), so that flow can be driven deterministically,
+//! including the rollback path where one write's `condition_expression` is
+//! rejected.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::{
+    types::{AttributeValue, Delete, Put, TransactWriteItem, Update},
+    Client,
+};
+
+use crate::{
+    constants::{BID_TABLE, BUYER_TABLE, ITEM_TABLE, PURCHASE_TABLE, SELLER_TABLE, TASK_TABLE},
+    errors::HandlerError,
+    transact::{is_transaction_canceled, transact_chunked},
+};
+
+/// A DynamoDB item key, keyed by attribute name.
+pub type Key = HashMap<String, AttributeValue>;
+/// A full DynamoDB item, keyed by attribute name.
+pub type GatewayItem = HashMap<String, AttributeValue>;
+
+/// One write inside a [`Gateway::transaction`] call.
+pub enum WriteOp {
+    Put {
+        table: &'static str,
+        item: GatewayItem,
+    },
+    Update {
+        table: &'static str,
+        key: Key,
+        update_expression: String,
+        condition_expression: Option<String>,
+        names: HashMap<String, String>,
+        values: HashMap<String, AttributeValue>,
+    },
+    Delete {
+        table: &'static str,
+        key: Key,
+        condition_expression: Option<String>,
+        names: HashMap<String, String>,
+        values: HashMap<String, AttributeValue>,
+    },
+}
+
+/// Storage operations route handlers need, kept narrow enough to be backed
+/// by an in-memory double in tests.
+#[async_trait]
+pub trait Gateway: Send + Sync {
+    async fn get_item(&self, table: &'static str, key: Key) -> Result<Option<GatewayItem>, HandlerError>;
+    async fn put_item(&self, table: &'static str, item: GatewayItem) -> Result<(), HandlerError>;
+    async fn query_items(
+        &self,
+        table: &'static str,
+        key_condition_expression: &str,
+        values: HashMap<String, AttributeValue>,
+    ) -> Result<Vec<GatewayItem>, HandlerError>;
+    /// Apply every `WriteOp` atomically: if any write's `condition_expression`
+    /// is rejected, none of them commit and the error is
+    /// `HandlerError::GatewayConditionCheckFailed` (see
+    /// `is_gateway_condition_check_failed`).
+    async fn transaction(&self, ops: Vec<WriteOp>) -> Result<(), HandlerError>;
+}
+
+/// Whether `err` is the condition-check failure `Gateway::transaction`
+/// returns when one of its `WriteOp`s' `condition_expression` was rejected.
+pub fn is_gateway_condition_check_failed(err: &HandlerError) -> bool {
+    matches!(err, HandlerError::GatewayConditionCheckFailed)
+}
+
+fn non_empty<V>(map: HashMap<String, V>) -> Option<HashMap<String, V>> {
+    (!map.is_empty()).then_some(map)
+}
+
+/// Production `Gateway` backed by a live DynamoDB table, via `transact_chunked`
+/// for `transaction` so it keeps working past DynamoDB's 25-action limit.
+pub struct DynamoGateway {
+    client: Client,
+}
+
+impl DynamoGateway {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Gateway for DynamoGateway {
+    async fn get_item(&self, table: &'static str, key: Key) -> Result<Option<GatewayItem>, HandlerError> {
+        let resp = self
+            .client
+            .get_item()
+            .table_name(table)
+            .set_key(Some(key))
+            .send()
+            .await?;
+        Ok(resp.item)
+    }
+
+    async fn put_item(&self, table: &'static str, item: GatewayItem) -> Result<(), HandlerError> {
+        self.client
+            .put_item()
+            .table_name(table)
+            .set_item(Some(item))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn query_items(
+        &self,
+        table: &'static str,
+        key_condition_expression: &str,
+        values: HashMap<String, AttributeValue>,
+    ) -> Result<Vec<GatewayItem>, HandlerError> {
+        let resp = self
+            .client
+            .query()
+            .table_name(table)
+            .key_condition_expression(key_condition_expression)
+            .set_expression_attribute_values(non_empty(values))
+            .send()
+            .await?;
+        Ok(resp.items().to_vec())
+    }
+
+    async fn transaction(&self, ops: Vec<WriteOp>) -> Result<(), HandlerError> {
+        let items = ops
+            .into_iter()
+            .map(|op| -> Result<TransactWriteItem, HandlerError> {
+                let item = match op {
+                    WriteOp::Put { table, item } => TransactWriteItem::builder()
+                        .put(Put::builder().table_name(table).set_item(Some(item)).build()?)
+                        .build(),
+                    WriteOp::Update {
+                        table,
+                        key,
+                        update_expression,
+                        condition_expression,
+                        names,
+                        values,
+                    } => {
+                        let mut builder = Update::builder()
+                            .table_name(table)
+                            .set_key(Some(key))
+                            .update_expression(update_expression)
+                            .set_expression_attribute_names(non_empty(names))
+                            .set_expression_attribute_values(non_empty(values));
+                        if let Some(condition) = condition_expression {
+                            builder = builder.condition_expression(condition);
+                        }
+                        TransactWriteItem::builder().update(builder.build()?).build()
+                    }
+                    WriteOp::Delete {
+                        table,
+                        key,
+                        condition_expression,
+                        names,
+                        values,
+                    } => {
+                        let mut builder = Delete::builder()
+                            .table_name(table)
+                            .set_key(Some(key))
+                            .set_expression_attribute_names(non_empty(names))
+                            .set_expression_attribute_values(non_empty(values));
+                        if let Some(condition) = condition_expression {
+                            builder = builder.condition_expression(condition);
+                        }
+                        TransactWriteItem::builder().delete(builder.build()?).build()
+                    }
+                };
+                Ok(item)
+            })
+            .collect::<Result<Vec<_>, HandlerError>>()?;
+
+        match transact_chunked(&self.client, items).await {
+            Ok(_) => Ok(()),
+            Err(e) if is_transaction_canceled(&e) => Err(HandlerError::GatewayConditionCheckFailed),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Attribute names forming each table's primary key, so [`InMemoryGateway`]
+/// can file a `put_item` under the same key a later `get_item`/`transaction`
+/// call would address it by. Extend this alongside any new table a handler
+/// ported onto `Gateway` reads or writes.
+fn key_attrs_for(table: &str) -> &'static [&'static str] {
+    match table {
+        t if t == ITEM_TABLE => &["sellerId", "id"],
+        t if t == SELLER_TABLE => &["id"],
+        t if t == BUYER_TABLE => &["id"],
+        t if t == BID_TABLE => &["buyerId", "id"],
+        t if t == PURCHASE_TABLE => &["buyerId", "id"],
+        t if t == TASK_TABLE => &["uid"],
+        _ => &[],
+    }
+}
+
+/// Canonical string for a key's attribute values, used as the in-memory
+/// table's `HashMap` key (`AttributeValue` itself isn't `Hash`).
+fn key_string(key: &Key) -> String {
+    let mut parts: Vec<(&str, String)> = key.iter().map(|(k, v)| (k.as_str(), format!("{v:?}"))).collect();
+    parts.sort();
+    parts
+        .into_iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn extract_key(table: &str, item: &GatewayItem) -> Key {
+    key_attrs_for(table)
+        .iter()
+        .filter_map(|attr| item.get(*attr).map(|v| ((*attr).to_string(), v.clone())))
+        .collect()
+}
+
+fn as_f64(value: &AttributeValue) -> Option<f64> {
+    match value {
+        AttributeValue::N(n) => n.parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_list_len(value: &AttributeValue) -> Option<usize> {
+    match value {
+        AttributeValue::L(l) => Some(l.len()),
+        _ => None,
+    }
+}
+
+fn format_numeric(n: f64) -> AttributeValue {
+    if n.fract() == 0.0 {
+        AttributeValue::N(format!("{}", n as i128))
+    } else {
+        AttributeValue::N(n.to_string())
+    }
+}
+
+/// Resolve `name` through `names` if it's a `#`-aliased placeholder,
+/// otherwise treat it as a literal attribute name.
+fn resolve_name<'a>(name: &'a str, names: &'a HashMap<String, String>) -> &'a str {
+    if name.starts_with('#') {
+        names.get(name).map(String::as_str).unwrap_or(name)
+    } else {
+        name
+    }
+}
+
+fn lookup_numeric(name: &str, item: Option<&GatewayItem>, names: &HashMap<String, String>) -> Option<f64> {
+    let attr = resolve_name(name, names);
+    item.and_then(|i| i.get(attr)).and_then(as_f64)
+}
+
+/// Evaluate one `name OP value` atom (`=`, `>=`, or `size(name) = value`)
+/// against `item`. See [`InMemoryGateway`] for the condition-expression
+/// shapes this is scoped to.
+fn eval_atom(
+    atom: &str,
+    item: Option<&GatewayItem>,
+    names: &HashMap<String, String>,
+    values: &HashMap<String, AttributeValue>,
+) -> bool {
+    if let Some((lhs, rhs)) = atom.split_once(">=") {
+        return match (lookup_numeric(lhs.trim(), item, names), values.get(rhs.trim()).and_then(as_f64)) {
+            (Some(l), Some(r)) => l >= r,
+            _ => false,
+        };
+    }
+
+    let Some((lhs, rhs)) = atom.split_once('=') else {
+        return false;
+    };
+    let lhs = lhs.trim();
+    let rhs_value = values.get(rhs.trim());
+
+    if let Some(inner) = lhs.strip_prefix("size(").and_then(|s| s.strip_suffix(')')) {
+        let len = item
+            .and_then(|i| i.get(resolve_name(inner, names)))
+            .and_then(as_list_len)
+            .unwrap_or(0);
+        let expect = rhs_value.and_then(as_f64).unwrap_or(-1.0);
+        return (len as f64) == expect;
+    }
+
+    let current = item.and_then(|i| i.get(resolve_name(lhs, names)));
+    match (current, rhs_value) {
+        (Some(c), Some(r)) => c == r,
+        (None, Some(AttributeValue::Null(true))) => true,
+        _ => false,
+    }
+}
+
+/// Evaluate a `condition_expression` against `item`. Only understands the
+/// shapes this crate's handlers actually build: `AND`-joined clauses, each
+/// an optional single `OR` chain of atoms.
+fn eval_condition(
+    expr: &str,
+    item: Option<&GatewayItem>,
+    names: &HashMap<String, String>,
+    values: &HashMap<String, AttributeValue>,
+) -> bool {
+    expr.split(" AND ").all(|clause| {
+        let clause = clause
+            .trim()
+            .strip_prefix('(')
+            .and_then(|c| c.strip_suffix(')'))
+            .unwrap_or(clause.trim());
+        clause.split(" OR ").any(|atom| eval_atom(atom.trim(), item, names, values))
+    })
+}
+
+/// Apply a `SET a = :x, b = y + :z` update expression to `item`. Only
+/// understands the shapes this crate's handlers actually build: plain
+/// `:value` assignment and single `attr +`/`attr -` increments.
+fn apply_update(
+    item: &mut GatewayItem,
+    update_expression: &str,
+    names: &HashMap<String, String>,
+    values: &HashMap<String, AttributeValue>,
+) {
+    let body = update_expression.strip_prefix("SET ").unwrap_or(update_expression);
+    for assignment in body.split(',') {
+        let Some((target, expr)) = assignment.trim().split_once('=') else {
+            continue;
+        };
+        let target = resolve_name(target.trim(), names).to_string();
+        let expr = expr.trim();
+
+        let new_value = if expr.starts_with(':') {
+            values.get(expr).cloned()
+        } else if let Some((lhs, token)) = expr.split_once(" + ") {
+            let base = lookup_numeric(lhs.trim(), Some(&*item), names).unwrap_or(0.0);
+            let delta = values.get(token.trim()).and_then(as_f64).unwrap_or(0.0);
+            Some(format_numeric(base + delta))
+        } else if let Some((lhs, token)) = expr.split_once(" - ") {
+            let base = lookup_numeric(lhs.trim(), Some(&*item), names).unwrap_or(0.0);
+            let delta = values.get(token.trim()).and_then(as_f64).unwrap_or(0.0);
+            Some(format_numeric(base - delta))
+        } else {
+            None
+        };
+
+        if let Some(value) = new_value {
+            item.insert(target, value);
+        }
+    }
+}
+
+/// Deterministic `Gateway` backed by process memory, used by tests that
+/// exercise handler logic without live AWS. See [`eval_condition`] and
+/// [`apply_update`] for the (intentionally narrow) expression support.
+#[derive(Default)]
+pub struct InMemoryGateway {
+    tables: Mutex<HashMap<&'static str, HashMap<String, GatewayItem>>>,
+}
+
+#[async_trait]
+impl Gateway for InMemoryGateway {
+    async fn get_item(&self, table: &'static str, key: Key) -> Result<Option<GatewayItem>, HandlerError> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables.get(table).and_then(|t| t.get(&key_string(&key))).cloned())
+    }
+
+    async fn put_item(&self, table: &'static str, item: GatewayItem) -> Result<(), HandlerError> {
+        let key = extract_key(table, &item);
+        self.tables
+            .lock()
+            .unwrap()
+            .entry(table)
+            .or_default()
+            .insert(key_string(&key), item);
+        Ok(())
+    }
+
+    async fn query_items(
+        &self,
+        table: &'static str,
+        key_condition_expression: &str,
+        values: HashMap<String, AttributeValue>,
+    ) -> Result<Vec<GatewayItem>, HandlerError> {
+        let Some((attr, token)) = key_condition_expression.split_once('=') else {
+            return Ok(Vec::new());
+        };
+        let Some(expected) = values.get(token.trim()) else {
+            return Ok(Vec::new());
+        };
+        let attr = attr.trim();
+
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .get(table)
+            .map(|t| {
+                t.values()
+                    .filter(|item| item.get(attr) == Some(expected))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn transaction(&self, ops: Vec<WriteOp>) -> Result<(), HandlerError> {
+        let mut tables = self.tables.lock().unwrap();
+
+        // Evaluate every condition against the pre-transaction snapshot before
+        // mutating anything, so a rejected write leaves every op uncommitted.
+        for op in &ops {
+            let (table, key, condition, names, values) = match op {
+                WriteOp::Put { .. } => continue,
+                WriteOp::Update {
+                    table,
+                    key,
+                    condition_expression,
+                    names,
+                    values,
+                    ..
+                } => (*table, key, condition_expression, names, values),
+                WriteOp::Delete {
+                    table,
+                    key,
+                    condition_expression,
+                    names,
+                    values,
+                } => (*table, key, condition_expression, names, values),
+            };
+            let Some(condition) = condition else { continue };
+            let current = tables.get(table).and_then(|t| t.get(&key_string(key)));
+            if !eval_condition(condition, current, names, values) {
+                return Err(HandlerError::GatewayConditionCheckFailed);
+            }
+        }
+
+        for op in ops {
+            match op {
+                WriteOp::Put { table, item } => {
+                    let key = extract_key(table, &item);
+                    tables.entry(table).or_default().insert(key_string(&key), item);
+                }
+                WriteOp::Update {
+                    table,
+                    key,
+                    update_expression,
+                    names,
+                    values,
+                    ..
+                } => {
+                    let key_str = key_string(&key);
+                    let table_map = tables.entry(table).or_default();
+                    let mut item = table_map.get(&key_str).cloned().unwrap_or_default();
+                    for (k, v) in &key {
+                        item.insert(k.clone(), v.clone());
+                    }
+                    apply_update(&mut item, &update_expression, &names, &values);
+                    table_map.insert(key_str, item);
+                }
+                WriteOp::Delete { table, key, .. } => {
+                    if let Some(table_map) = tables.get_mut(table) {
+                        table_map.remove(&key_string(&key));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
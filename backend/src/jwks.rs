@@ -0,0 +1,100 @@
+//! Periodic JWKS refresh backing asymmetric (RS256/ES256) JWT verification.
+//!
+//! `auth_middleware` used to hardcode `Algorithm::HS256` against a single
+//! shared secret, so every verifying service had to hold that secret and
+//! rotating it meant downtime. [`JwksStore`] keeps a `kid` -> `DecodingKey`
+//! map fresh by periodically re-fetching a JWKS document, so operators can
+//! rotate signing keys and run verify-only services without sharing secrets.
+//! Tokens without a `kid` still fall back to the existing HS256 path.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use jsonwebtoken::{
+    jwk::{JwkSet, KeyAlgorithm},
+    Algorithm, DecodingKey,
+};
+use lambda_http::tracing;
+use tokio::sync::RwLock;
+
+#[derive(Clone)]
+struct JwkEntry {
+    key: DecodingKey,
+    algorithm: Algorithm,
+}
+
+/// `kid` -> `DecodingKey` map, refreshed in the background by [`spawn_jwks_refresh`].
+#[derive(Default)]
+pub struct JwksStore {
+    keys: RwLock<HashMap<String, JwkEntry>>,
+}
+
+impl JwksStore {
+    /// Look up the decoding key and algorithm registered for `kid`, if any.
+    pub async fn get(&self, kid: &str) -> Option<(DecodingKey, Algorithm)> {
+        self.keys
+            .read()
+            .await
+            .get(kid)
+            .map(|entry| (entry.key.clone(), entry.algorithm))
+    }
+
+    async fn replace(&self, jwks: JwkSet) {
+        let mut next = HashMap::new();
+        for jwk in jwks.keys {
+            let Some(kid) = jwk.common.key_id.clone() else {
+                continue;
+            };
+            let Some(algorithm) = jwk
+                .common
+                .key_algorithm
+                .and_then(key_algorithm_to_algorithm)
+            else {
+                continue;
+            };
+            let Ok(key) = DecodingKey::from_jwk(&jwk) else {
+                continue;
+            };
+            next.insert(kid, JwkEntry { key, algorithm });
+        }
+        *self.keys.write().await = next;
+    }
+}
+
+fn key_algorithm_to_algorithm(alg: KeyAlgorithm) -> Option<Algorithm> {
+    match alg {
+        KeyAlgorithm::RS256 => Some(Algorithm::RS256),
+        KeyAlgorithm::ES256 => Some(Algorithm::ES256),
+        _ => None,
+    }
+}
+
+/// Fetch `jwks_url` and refresh `store`, logging (not failing) on error so a
+/// transient fetch failure doesn't drop already-cached keys.
+async fn refresh_once(http: &reqwest::Client, jwks_url: &str, store: &JwksStore) {
+    let jwks = match http.get(jwks_url).send().await {
+        Ok(resp) => resp.json::<JwkSet>().await,
+        Err(e) => {
+            tracing::warn!("failed to fetch JWKS document from {jwks_url}: {e}");
+            return;
+        }
+    };
+    match jwks {
+        Ok(jwks) => store.replace(jwks).await,
+        Err(e) => tracing::warn!("failed to parse JWKS document from {jwks_url}: {e}"),
+    }
+}
+
+/// Spawn the background worker that keeps `store` in sync with `jwks_url`,
+/// mirroring `spawn_settlement_worker`'s periodic-tick pattern in `main.rs`.
+/// The first tick fires immediately, so keys are populated before the first
+/// request needs them.
+pub fn spawn_jwks_refresh(jwks_url: String, store: Arc<JwksStore>, interval_secs: u64) {
+    tokio::spawn(async move {
+        let http = reqwest::Client::new();
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            ticker.tick().await;
+            refresh_once(&http, &jwks_url, &store).await;
+        }
+    });
+}
@@ -0,0 +1,78 @@
+//! Outbound mail abstraction backing the email-verification flow.
+//!
+//! `register_finish` needs to deliver a verification link without the rest
+//! of `routes::auth` caring whether that happens over SES or not at all, so
+//! [`Mailer`] gives it a narrow, testable surface: [`SesMailer`] for
+//! production, [`NoopMailer`] (just logs) for tests, mirroring how
+//! [`crate::gateway::Gateway`] decouples handlers from the DynamoDB SDK.
+
+use async_trait::async_trait;
+use aws_sdk_sesv2::{
+    types::{Body, Content, Destination, EmailContent, Message},
+    Client,
+};
+use lambda_http::tracing;
+
+use crate::errors::HandlerError;
+
+/// Send a single plain-text email, e.g. a verification or password-reset link.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, to: &str, subject: &str, body_text: &str) -> Result<(), HandlerError>;
+}
+
+/// Production `Mailer` backed by Amazon SES.
+pub struct SesMailer {
+    client: Client,
+    from_address: String,
+}
+
+impl SesMailer {
+    pub fn new(client: Client, from_address: String) -> Self {
+        Self {
+            client,
+            from_address,
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for SesMailer {
+    async fn send(&self, to: &str, subject: &str, body_text: &str) -> Result<(), HandlerError> {
+        let content = EmailContent::builder()
+            .simple(
+                Message::builder()
+                    .subject(Content::builder().data(subject).build()?)
+                    .body(
+                        Body::builder()
+                            .text(Content::builder().data(body_text).build()?)
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build();
+
+        self.client
+            .send_email()
+            .from_email_address(&self.from_address)
+            .destination(Destination::builder().to_addresses(to).build())
+            .content(content)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Test/dev `Mailer` that just logs, so the verification flow is exercisable
+/// without a real SES identity.
+#[derive(Default)]
+pub struct NoopMailer;
+
+#[async_trait]
+impl Mailer for NoopMailer {
+    async fn send(&self, to: &str, subject: &str, body_text: &str) -> Result<(), HandlerError> {
+        tracing::info!("NoopMailer: would send {to:?} subject={subject:?} body={body_text:?}");
+        Ok(())
+    }
+}
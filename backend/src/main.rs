@@ -1,9 +1,15 @@
 mod constants;
 mod errors;
+mod events;
+mod gateway;
+mod jwks;
+mod mailer;
 mod middlewares;
 mod models;
+mod opaque;
 mod routes;
 mod state;
+mod transact;
 mod utils;
 
 #[cfg(test)]
@@ -12,7 +18,7 @@ mod tests;
 use std::{
     env,
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use axum::{
@@ -22,6 +28,7 @@ use axum::{
     routing::get,
     Extension, Router,
 };
+use constants::{DEFAULT_AUCTION_SETTLEMENT_INTERVAL_SECS, DEFAULT_JWKS_REFRESH_INTERVAL_SECS};
 use errors::HandlerError;
 use lambda_http::{run, tracing, Error};
 use serde::{Deserialize, Serialize};
@@ -82,10 +89,20 @@ pub async fn create_service(state: Arc<AppState>) -> Result<Router, Error> {
         .merge(routes::auth::router())
         .with_state(state.clone());
 
+    // Dumps expose every table's raw rows (fund balances included) and let the
+    // importer overwrite arbitrary rows, so that subtree gets an extra
+    // `admin_only` layer on top of the `auth_middleware` the rest of `auth_router`
+    // already requires.
+    let admin_dump_router = routes::dump::router().layer(middleware::from_fn(middlewares::admin_only));
+
     let auth_router = OpenApiRouter::new()
         .route("/v1/ping", get(ping))
+        .merge(routes::auth::protected_router())
+        .nest("/v1/buyer", routes::buyer::route())
         .nest("/v1/item", routes::item::router())
         .nest("/v1/seller", routes::seller::router())
+        .nest("/v1/tasks", routes::task::router())
+        .nest("/v1/dumps", admin_dump_router)
         .layer(middleware::from_fn_with_state(
             state.clone(),
             middlewares::auth::auth_middleware,
@@ -106,6 +123,40 @@ pub async fn create_service(state: Arc<AppState>) -> Result<Router, Error> {
     Ok(service)
 }
 
+/// Spawn the background worker that periodically settles `Active` items
+/// whose `endDate` has passed, so auctions don't get stuck waiting on a
+/// seller to manually fulfill.
+fn spawn_settlement_worker(state: Arc<AppState>) {
+    let interval_secs = env::var("AUCTION_SETTLEMENT_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_AUCTION_SETTLEMENT_INTERVAL_SECS);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            ticker.tick().await;
+            if let Err(e) = routes::task::run_settlement_sweep(&state).await {
+                tracing::warn!("auction settlement sweep failed: {e}");
+            }
+        }
+    });
+}
+
+/// Spawn the JWKS refresh worker if `JWKS_URL` is configured, so operators
+/// that haven't opted into asymmetric verification pay no extra cost.
+fn spawn_jwks_worker(state: &AppState) {
+    let Ok(jwks_url) = env::var("JWKS_URL") else {
+        return;
+    };
+    let interval_secs = env::var("JWKS_REFRESH_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_JWKS_REFRESH_INTERVAL_SECS);
+
+    jwks::spawn_jwks_refresh(jwks_url, state.jwks.clone(), interval_secs);
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     env::set_var("AWS_LAMBDA_LOG_LEVEL", "WARN");
@@ -114,6 +165,8 @@ async fn main() -> Result<(), Error> {
     tracing::info!("API Handler Start!!!");
 
     let state = Arc::new(AppState::new().await?);
+    spawn_settlement_worker(state.clone());
+    spawn_jwks_worker(&state);
     let service = create_service(state).await?;
 
     run(service).await
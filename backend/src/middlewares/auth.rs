@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use aws_sdk_dynamodb::{types::AttributeValue, Client};
 use axum::{
     body::Body,
     extract::{Request, State},
@@ -9,7 +10,32 @@ use axum::{
 };
 use jsonwebtoken::{Algorithm, Validation};
 
-use crate::{errors::HandlerError, models::auth::ClaimOwned, state::AppState};
+use crate::{
+    constants::ACCESS_TOKEN_TABLE, errors::HandlerError, models::auth::ClaimOwned, state::AppState,
+};
+
+async fn token_is_valid(state: &AppState, jti: &str) -> Result<bool, HandlerError> {
+    if let Some(valid) = state.token_cache.get(jti).await {
+        return Ok(valid);
+    }
+
+    let client = Client::new(&state.aws_config);
+    let resp = client
+        .get_item()
+        .table_name(ACCESS_TOKEN_TABLE)
+        .key("tokenId", AttributeValue::S(jti.to_string()))
+        .send()
+        .await?;
+
+    let valid = resp
+        .item
+        .and_then(|item| item.get("valid").and_then(|v| v.as_bool().ok()).copied())
+        .unwrap_or(false);
+
+    state.token_cache.insert(jti.to_string(), valid).await;
+
+    Ok(valid)
+}
 
 pub async fn auth_middleware(
     State(state): State<Arc<AppState>>,
@@ -42,9 +68,35 @@ pub async fn auth_middleware(
         "Empty token value".to_string(),
     ))?;
 
-    let mut validation = Validation::new(Algorithm::HS256);
-    validation.set_audience(&["auction-house-rs"]);
-    let data = jsonwebtoken::decode::<ClaimOwned>(token, &state.jwt.1, &validation)?;
+    let header = jsonwebtoken::decode_header(token)?;
+    let data = match header.kid {
+        // Asymmetric tokens carry a `kid` identifying which JWKS entry signed
+        // them, so operators can rotate keys without every verifier sharing
+        // a secret. An unrecognized `kid` is rejected rather than falling
+        // back to HS256, since accepting it there would let a token signed
+        // under a retired/unknown key slip through.
+        Some(kid) => {
+            let (decoding_key, algorithm) = state.jwks.get(&kid).await.ok_or_else(|| {
+                HandlerError::HandlerError(
+                    StatusCode::UNAUTHORIZED,
+                    "Unknown JWT key id".to_string(),
+                )
+            })?;
+            let mut validation = Validation::new(algorithm);
+            validation.set_audience(&["auction-house-rs"]);
+            jsonwebtoken::decode::<ClaimOwned>(token, &decoding_key, &validation)?
+        }
+        None => {
+            let mut validation = Validation::new(Algorithm::HS256);
+            validation.set_audience(&["auction-house-rs"]);
+            jsonwebtoken::decode::<ClaimOwned>(token, &state.jwt.1, &validation)?
+        }
+    };
+
+    if !token_is_valid(&state, &data.claims.jti).await? {
+        return Err(HandlerError::TokenRevokedError);
+    }
+
     req.extensions_mut().insert(data.claims);
 
     Ok(next.run(req).await)
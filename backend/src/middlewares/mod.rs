@@ -1,9 +1,22 @@
-use axum::{extract::Request, middleware::Next, response::IntoResponse};
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
 use lambda_http::{request::RequestContext, tracing};
 
+use crate::models::{auth::ClaimOwned, user::UserType};
+
 pub mod auth;
 
-pub async fn trace_client(req: Request, next: Next) -> impl IntoResponse {
+/// Caller's source IP, captured by [`trace_client`] and consulted by session-minting
+/// handlers (e.g. `routes::auth::login_finish`) to annotate a session's origin.
+/// Absent outside API Gateway (e.g. in tests), in which case it's just omitted.
+#[derive(Debug, Clone)]
+pub struct ClientIp(pub String);
+
+pub async fn trace_client(mut req: Request, next: Next) -> impl IntoResponse {
     let ctx = req.extensions().get::<RequestContext>();
     if let Some(RequestContext::ApiGatewayV2(v2ctx)) = ctx {
         let http_ctx = &v2ctx.http;
@@ -14,6 +27,24 @@ pub async fn trace_client(req: Request, next: Next) -> impl IntoResponse {
         let path = req.uri().path();
 
         tracing::info!("{} -> {}", source_ip, path);
+        req.extensions_mut().insert(ClientIp(source_ip.to_string()));
+    }
+    next.run(req).await
+}
+
+/// Reject any request whose caller isn't an `Admin`. Nest this inside
+/// `auth::auth_middleware` on admin-only routers (it reads the `ClaimOwned`
+/// that middleware already verified and inserted, rather than decoding the
+/// JWT a second time), so later admin subsystems can reuse it with one layer.
+pub async fn admin_only(req: Request, next: Next) -> Response {
+    let is_admin = req
+        .extensions()
+        .get::<ClaimOwned>()
+        .is_some_and(|claim| claim.user_type == UserType::Admin);
+
+    if !is_admin {
+        return (StatusCode::FORBIDDEN, "Admin access required").into_response();
     }
+
     next.run(req).await
 }
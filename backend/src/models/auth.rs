@@ -7,7 +7,25 @@ use super::user::UserType;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct RegisterPayload {
+pub struct RegistrationStartRequest {
+    /// User Email
+    pub email: String,
+    /// User type of the user.
+    pub user_type: UserType,
+    /// Hex-encoded OPAQUE `RegistrationRequest` (blinded password element).
+    pub registration_request: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationStartResponse {
+    /// Hex-encoded OPAQUE `RegistrationResponse`.
+    pub registration_response: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationFinishRequest {
     /// User first name
     pub first_name: String,
     /// User last name
@@ -16,19 +34,48 @@ pub struct RegisterPayload {
     pub email: String,
     /// User type of the user.
     pub user_type: UserType,
-    /// Password in bcrypt
-    pub password: String,
+    /// Hex-encoded OPAQUE `RegistrationUpload`.
+    pub registration_upload: String,
+    /// Required when `user_type` is `Admin`, checked against the
+    /// `ADMIN_INVITE_SECRET` environment variable; ignored otherwise.
+    #[serde(default)]
+    pub invite_secret: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct LoginPayload {
+pub struct LoginStartRequest {
     /// User Email
     pub email: String,
     /// User type of the user.
     pub user_type: UserType,
+    /// Hex-encoded OPAQUE `CredentialRequest`.
+    pub credential_request: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginStartResponse {
+    /// Hex-encoded OPAQUE `CredentialResponse`.
+    pub credential_response: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginFinishRequest {
+    /// User Email
+    pub email: String,
+    /// User type of the user.
+    pub user_type: UserType,
+    /// Hex-encoded OPAQUE `CredentialFinalization`.
+    pub credential_finalization: String,
+}
+
+/// Pre-OPAQUE scrypt challenge-response shape. `register`/`login` moved to
+/// the OPAQUE aPAKE (see `RegistrationStartRequest`/`LoginStartRequest`),
+/// which already keeps the password off the wire and is strictly stronger
+/// than a hashed-challenge comparison, so this is kept unwired rather than
+/// reintroducing scrypt password storage alongside it.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct LoginChallenge {
@@ -42,6 +89,7 @@ pub struct LoginChallenge {
     pub p: u32,
 }
 
+/// See [`LoginChallenge`].
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginChallengeAnswer {
@@ -53,6 +101,85 @@ pub struct LoginChallengeAnswer {
     pub password_hash: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NonceResponse {
+    /// Nonce to embed in the SIWE message's `Nonce` field.
+    pub nonce: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletLoginPayload {
+    /// EIP-4361 SIWE message, exactly as signed.
+    pub message: String,
+    /// Hex-encoded (`0x`-prefixed) 65-byte secp256k1 signature over the message.
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResendVerificationRequest {
+    /// User Email
+    pub email: String,
+    /// User type of the user.
+    pub user_type: UserType,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordRequest {
+    /// User Email
+    pub email: String,
+    /// User type of the user.
+    pub user_type: UserType,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordResetStartRequest {
+    /// Single-use token from the password-reset email.
+    pub token: String,
+    /// Hex-encoded OPAQUE `RegistrationRequest` (blinded new password element).
+    pub registration_request: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordResetFinishRequest {
+    /// Single-use token from the password-reset email.
+    pub token: String,
+    /// Hex-encoded OPAQUE `RegistrationUpload`.
+    pub registration_upload: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenRequest {
+    /// Refresh token returned alongside the JWT by register/login/refresh.
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthAuthorizeResponse {
+    /// Provider authorization URL to redirect the user-agent to.
+    pub authorize_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+    /// Session id, i.e. the refresh token identifying this session.
+    pub id: String,
+    /// When this session was created, in unix timestamp seconds.
+    pub issued_at: u64,
+    /// When this session's refresh token expires, in unix timestamp seconds.
+    pub expire_at: u64,
+    /// Source IP the session was created from, if known.
+    pub source_ip: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub struct Claim<'a> {
@@ -72,6 +199,11 @@ pub struct Claim<'a> {
     pub iat: u64,
     /// Audience
     pub aud: &'a str,
+    /// Token ID, used to look up this token's revocation status.
+    pub jti: &'a str,
+    /// Session ID this token's refresh token is tracked under, used to
+    /// revoke the whole session (and therefore every future refresh) at once.
+    pub sid: &'a str,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -93,6 +225,11 @@ pub struct ClaimOwned {
     pub iat: u64,
     /// Audience
     pub aud: String,
+    /// Token ID, used to look up this token's revocation status.
+    pub jti: String,
+    /// Session ID this token's refresh token is tracked under, used to
+    /// revoke the whole session (and therefore every future refresh) at once.
+    pub sid: String,
 }
 
 impl ClaimOwned {
@@ -106,6 +243,8 @@ impl ClaimOwned {
             exp: self.exp,
             iat: self.iat,
             aud: &self.aud,
+            jti: &self.jti,
+            sid: &self.sid,
         }
     }
 }
@@ -15,8 +15,11 @@ pub struct Bid {
     pub create_at: u64,
     /// Target item's hash & range key.
     pub item: ItemRef,
-    /// Bid amount.
+    /// Visible current bid amount.
     pub amount: u64,
+    /// Hidden maximum the bidder is willing to pay; the proxy-bidding engine
+    /// only ever raises `amount` up to this ceiling.
+    pub max_amount: u64,
     /// Is active bid.
     pub is_active: bool,
 }
@@ -63,6 +66,82 @@ pub struct BidItemRequest {
     pub seller_id: String,
     /// ID of the item
     pub id: Ulid,
-    /// Bid amount
-    pub amount: u64,
+    /// Hidden maximum the bidder is willing to pay; the visible amount is
+    /// derived by the proxy-bidding engine.
+    pub max_amount: u64,
+}
+
+/// Outcome of resolving a new proxy bid against the current leader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProxyBidOutcome {
+    /// Whether the incoming bid becomes the new leader.
+    pub new_bid_wins: bool,
+    /// Visible `amount` the winning bid (new or incumbent) should carry.
+    pub leader_amount: u64,
+}
+
+/// Kind of live update pushed over the `/buyer/bid-stream` and `/item/{sellerId}/{itemId}/events` SSE connections.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum BidEventKind {
+    /// Another buyer's bid superseded `target_buyer_id`'s leading bid.
+    Outbid,
+    /// The visible current bid on the item changed.
+    PriceUpdate,
+    /// `target_buyer_id`'s bid won the item at settlement.
+    Won,
+    /// The item's auction ended.
+    AuctionEnded,
+    /// The item went live for bidding.
+    Published,
+    /// The item was pulled back to inactive before it received any bids.
+    Unpublished,
+    /// The seller fulfilled the item's winning bid.
+    Completed,
+}
+
+/// Live bid update published on an item's channel in `AppState::bid_events`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BidEvent {
+    /// What happened.
+    pub kind: BidEventKind,
+    /// Item the event concerns.
+    pub item: ItemRef,
+    /// Item's new leading bid, where relevant.
+    pub current_bid: Option<BidRef>,
+    /// Item's new visible current amount, where relevant.
+    pub amount: Option<u64>,
+    /// Buyer this event is meant for; `None` means every subscriber on the item.
+    pub target_buyer_id: Option<String>,
+}
+
+/// Resolve an incoming proxy bid against the current leader's hidden
+/// maximum, per eBay-style automatic bidding rules:
+///
+/// - No leader yet: the incoming bid wins at `max(init_price, min_increment)`.
+/// - Incoming max strictly exceeds the leader's max: the incoming bid wins,
+///   visible at `min(new_max, leader_max + min_increment)`.
+/// - Otherwise the leader stays (ties included, since the leader was placed
+///   first), visible amount bumped to `min(leader_max, new_max + min_increment)`.
+pub fn resolve_proxy_bid(
+    leader_max: Option<u64>,
+    new_max: u64,
+    init_price: u64,
+    min_increment: u64,
+) -> ProxyBidOutcome {
+    match leader_max {
+        None => ProxyBidOutcome {
+            new_bid_wins: true,
+            leader_amount: init_price.max(min_increment),
+        },
+        Some(leader_max) if new_max > leader_max => ProxyBidOutcome {
+            new_bid_wins: true,
+            leader_amount: new_max.min(leader_max + min_increment),
+        },
+        Some(leader_max) => ProxyBidOutcome {
+            new_bid_wins: false,
+            leader_amount: leader_max.min(new_max + min_increment),
+        },
+    }
 }
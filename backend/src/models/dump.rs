@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+use utoipa::ToSchema;
+
+/// Lifecycle status of a `Dump` export/import job.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DumpStatus {
+    Enqueued,
+    InProgress,
+    Done,
+    Failed,
+}
+
+/// An export job that snapshots all auction data into a single portable
+/// NDJSON archive in S3.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Dump {
+    /// Ulid, hash key
+    pub id: Ulid,
+    /// Current job status.
+    pub status: DumpStatus,
+    /// Unix timestamp the job was created.
+    pub created_at: u64,
+    /// Unix timestamp the job finished, if any.
+    pub finished_at: Option<u64>,
+    /// S3 key of the finished archive, set once `status == Done`.
+    pub download_key: Option<String>,
+    /// Failure reason, set when `status == Failed`.
+    pub error: Option<String>,
+}
+
+impl Dump {
+    pub fn new() -> Self {
+        Self {
+            id: Ulid::new(),
+            status: DumpStatus::Enqueued,
+            created_at: chrono::Local::now().timestamp_millis() as u64,
+            finished_at: None,
+            download_key: None,
+            error: None,
+        }
+    }
+}
+
+impl Default for Dump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One NDJSON line in a dump archive: which table it came from, the
+/// archive's schema version, and the raw DynamoDB item as JSON.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpRecord {
+    pub table: String,
+    pub schema_version: u32,
+    pub item: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRequest {
+    /// S3 key of a previously exported dump archive to restore.
+    pub archive_key: String,
+}
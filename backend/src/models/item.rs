@@ -1,10 +1,13 @@
 use core::fmt;
 
 use aws_sdk_dynamodb::types::AttributeValue;
+use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
 use ulid::Ulid;
 use utoipa::ToSchema;
 
+use crate::errors::HandlerError;
+
 use super::bid::BidRef;
 
 /// Item State Enum
@@ -43,6 +46,122 @@ impl fmt::Display for ItemState {
     }
 }
 
+/// DynamoDB attribute the item's lifecycle state is stored under. Handlers
+/// that gate on `state` without going through an `ItemTransition` (e.g.
+/// deleting an `InActive` item) should still reference this rather than
+/// hand-writing the attribute name.
+pub const ITEM_STATE_ATTR: &str = "state";
+
+/// One legal step in an item's lifecycle: the states it may start from and
+/// the state it lands in. Declaring every edge here, instead of leaving each
+/// handler to hand-roll its own `condition_expression`, keeps the guards in
+/// one audited place so they can't drift out of sync with each other (as
+/// `seller_archive_item_by_id` and `seller_unpublish_item_by_id` once did).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemTransition {
+    /// `InActive` -> `Active`: seller publishes a listing.
+    Publish,
+    /// `Active` -> `InActive`: seller pulls an unbid listing back.
+    Unpublish,
+    /// `Active` -> `Completed`: the auction's end date passed with a winning bid.
+    Settle,
+    /// `Active` -> `Failed`: the auction's end date passed with no bids.
+    SettleUnsold,
+    /// `Completed` -> `Archived`: seller fulfills the winning bid.
+    Fulfill,
+    /// `InActive` | `Failed` -> `Archived`: seller withdraws a listing that never sold.
+    Archive,
+}
+
+impl ItemTransition {
+    /// States this transition may legally start from.
+    pub fn from_states(self) -> &'static [ItemState] {
+        match self {
+            ItemTransition::Publish => &[ItemState::InActive],
+            ItemTransition::Unpublish => &[ItemState::Active],
+            ItemTransition::Settle => &[ItemState::Active],
+            ItemTransition::SettleUnsold => &[ItemState::Active],
+            ItemTransition::Fulfill => &[ItemState::Completed],
+            ItemTransition::Archive => &[ItemState::InActive, ItemState::Failed],
+        }
+    }
+
+    /// State this transition lands in.
+    pub fn to_state(self) -> ItemState {
+        match self {
+            ItemTransition::Publish => ItemState::Active,
+            ItemTransition::Unpublish => ItemState::InActive,
+            ItemTransition::Settle => ItemState::Completed,
+            ItemTransition::SettleUnsold => ItemState::Failed,
+            ItemTransition::Fulfill => ItemState::Archived,
+            ItemTransition::Archive => ItemState::Archived,
+        }
+    }
+
+    /// Whether `current` is a legal starting point for this transition.
+    pub fn allowed_from(self, current: &ItemState) -> bool {
+        self.from_states().contains(current)
+    }
+
+    /// Consistent 400 for a handler that checked `allowed_from` itself and
+    /// found the item in the wrong state ahead of time.
+    pub fn invalid_state_error(self) -> HandlerError {
+        HandlerError::HandlerError(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Item must be in state {:?} for this action",
+                self.from_states()
+            ),
+        )
+    }
+
+    /// Consistent 409 for a handler that only discovers the item was in the
+    /// wrong state when this transition's DynamoDB condition expression
+    /// rejects a concurrent write.
+    pub fn conflict_error(self) -> HandlerError {
+        HandlerError::HandlerError(
+            StatusCode::CONFLICT,
+            format!(
+                "Item left state {:?} before this action committed",
+                self.from_states()
+            ),
+        )
+    }
+
+    /// DynamoDB condition expression (`#state = :fromState0 OR ...`) guarding
+    /// this transition, plus the attribute values it references — including
+    /// `:toState`, the value callers should `SET #state =` to. Callers still
+    /// need `.expression_attribute_names("#state", ITEM_STATE_ATTR)` and to
+    /// fold `:toState` into their own `SET` clause alongside whatever other
+    /// fields that transition's handler also writes.
+    pub fn guard(self) -> (String, Vec<(String, AttributeValue)>) {
+        let from = self.from_states();
+        let condition_expression = (0..from.len())
+            .map(|i| format!("#state = :fromState{i}"))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+
+        let mut values: Vec<(String, AttributeValue)> = from
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (format!(":fromState{i}"), state.clone().into()))
+            .collect();
+        values.push((":toState".to_string(), self.to_state().into()));
+
+        (condition_expression, values)
+    }
+}
+
+/// A stored item image: the full-resolution upload plus its generated thumbnail.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemImage {
+    /// S3 key of the original, full-resolution upload.
+    pub original: String,
+    /// S3 key of the downscaled thumbnail (longest edge capped, aspect ratio preserved).
+    pub thumbnail: String,
+}
+
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Item {
@@ -62,8 +181,8 @@ pub struct Item {
     pub state: ItemState,
     /// Length of Auction, in unix timestamp diff.
     pub auction_length: u64,
-    /// List of S3 keys
-    pub images: Vec<String>,
+    /// Uploaded images, original + thumbnail key per image.
+    pub images: Vec<ItemImage>,
     /// Is Frozen
     pub is_frozen: bool,
     /// Unix timestamp, Some when item_state == "active"
@@ -72,6 +191,12 @@ pub struct Item {
     pub end_date: Option<u64>,
     /// Current bid's hash & range key.
     pub current_bid: Option<BidRef>,
+    /// Visible amount of `current_bid`, denormalized so `buyer_place_bid` can
+    /// condition its update on it without a second read from the bid table.
+    pub current_bid_amount: Option<u64>,
+    /// Bumped every time `current_bid` changes; lets `buyer_place_bid` detect a
+    /// stale read and retry instead of racing another buyer's bid.
+    pub bid_version: u64,
     /// List of past bids' hash & range key.
     pub past_bids: Vec<BidRef>,
     /// Item sold bid
@@ -98,7 +223,7 @@ impl Item {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ItemRef {
     // User id, hash key
@@ -127,8 +252,8 @@ pub struct AddItemRequest {
     pub init_price: u64,
     /// Length of Auction, in unix timestamp diff.
     pub auction_length: u64,
-    /// List of S3 keys
-    pub images: Vec<String>,
+    /// Pre-uploaded images (original + thumbnail key pairs).
+    pub images: Vec<ItemImage>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
@@ -142,8 +267,83 @@ pub struct UpdateItemRequest {
     pub init_price: Option<u64>,
     /// Length of Auction, in unix timestamp diff.
     pub auction_length: Option<u64>,
-    /// List of S3 keys
-    pub images: Option<Vec<String>>,
+    /// Pre-uploaded images (original + thumbnail key pairs).
+    pub images: Option<Vec<ItemImage>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckItemExiprationResponse {
+    /// User id, hash key
+    pub seller_id: String,
+    /// Ulid, range key
+    pub id: Ulid,
+    /// Whether the item's auction has passed its end date.
+    pub is_expired: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImagePresignRequest {
+    /// Seller of the item, must match the caller's claim.
+    pub seller_id: String,
+    /// Item the images belong to.
+    pub item_id: Ulid,
+    /// MIME type the client intends to upload (e.g. `image/jpeg`).
+    pub content_type: String,
+    /// Number of upload URLs to mint.
+    pub count: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PresignedImageUpload {
+    /// S3 object key to store on the item once uploaded.
+    pub key: String,
+    /// Time-limited presigned `PUT` URL.
+    pub upload_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImagePresignResponse {
+    /// One upload URL per requested image.
+    pub uploads: Vec<PresignedImageUpload>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PresignedUrl {
+    /// S3 object key as stored on the item.
+    pub key: String,
+    /// Time-limited presigned `GET` URL.
+    pub download_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PresignedImageDownload {
+    /// Presigned URL for the full-resolution original.
+    pub original: PresignedUrl,
+    /// Presigned URL for the downscaled thumbnail.
+    pub thumbnail: PresignedUrl,
+}
+
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct ListQuery {
+    /// Maximum number of items to return in this page.
+    pub limit: Option<u32>,
+    /// Opaque cursor returned as `nextCursor` on a previous page.
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PagedItemsResponse {
+    /// Items in this page.
+    pub items: Vec<Item>,
+    /// Opaque cursor for the next page, or `None` when exhausted.
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
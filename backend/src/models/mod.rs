@@ -8,7 +8,13 @@ use utoipa::ToSchema;
 
 pub mod auth;
 pub mod bid;
+pub mod buyer;
+pub mod dump;
 pub mod item;
+pub mod oauth;
+pub mod search;
+pub mod siwe;
+pub mod task;
 pub mod user;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::item::{Item, ItemState};
+
+const NAME_WEIGHT: f64 = 3.0;
+const DESCRIPTION_WEIGHT: f64 = 1.0;
+const EXACT_WEIGHT: f64 = 1.0;
+const PREFIX_WEIGHT: f64 = 0.6;
+const FUZZY_WEIGHT: f64 = 0.3;
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchRequest {
+    /// Free-text query, tokenized and matched against name/description.
+    #[serde(default)]
+    pub query: String,
+    /// Restrict to a single item state.
+    pub state: Option<ItemState>,
+    /// Minimum init_price, inclusive.
+    pub min_price: Option<u64>,
+    /// Maximum init_price, inclusive.
+    pub max_price: Option<u64>,
+    /// Restrict to a single seller.
+    pub seller_id: Option<String>,
+    /// Result sort key, defaults to relevance.
+    #[serde(default)]
+    pub sort: SortKey,
+    /// Number of hits to skip.
+    #[serde(default)]
+    pub offset: usize,
+    /// Max hits to return, defaults to 20.
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    #[default]
+    Relevance,
+    CreateAtDesc,
+    CreateAtAsc,
+    PriceAsc,
+    PriceDesc,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    /// Matched item.
+    pub item: Item,
+    /// Relevance score, higher is better.
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResponse {
+    /// Page of ranked hits.
+    pub hits: Vec<SearchHit>,
+    /// Total number of hits before pagination was applied.
+    pub estimated_total_hits: usize,
+    /// Wall-clock time spent ranking/sorting/paginating, in milliseconds.
+    pub processing_time_ms: u64,
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn fuzzy_tolerance(term_len: usize) -> usize {
+    if term_len >= 8 {
+        2
+    } else if term_len >= 4 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Wagner-Fischer edit distance between two short strings.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        let mut prev = row[0];
+        row[0] = i;
+        for j in 1..=b.len() {
+            let tmp = row[j];
+            row[j] = if a[i - 1] == b[j - 1] {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(row[j - 1])
+            };
+            prev = tmp;
+        }
+    }
+
+    row[b.len()]
+}
+
+fn term_match_weight(term: &str, token: &str) -> f64 {
+    if token == term {
+        return EXACT_WEIGHT;
+    }
+    if term.len() >= 3 && token.starts_with(term) {
+        return PREFIX_WEIGHT;
+    }
+    let tolerance = fuzzy_tolerance(term.len());
+    if tolerance > 0 && levenshtein(term, token) <= tolerance {
+        return FUZZY_WEIGHT;
+    }
+    0.0
+}
+
+fn best_field_match(term: &str, tokens: &[String]) -> f64 {
+    tokens
+        .iter()
+        .map(|token| term_match_weight(term, token))
+        .fold(0.0, f64::max)
+}
+
+/// Score an item against a tokenized query. Zero means no match on any term.
+fn score_item(item: &Item, terms: &[String]) -> f64 {
+    let name_tokens = tokenize(&item.name);
+    let description_tokens = tokenize(&item.description);
+
+    terms
+        .iter()
+        .map(|term| {
+            let name_score = best_field_match(term, &name_tokens) * NAME_WEIGHT;
+            let description_score =
+                best_field_match(term, &description_tokens) * DESCRIPTION_WEIGHT;
+            name_score.max(description_score)
+        })
+        .sum()
+}
+
+/// Rank, sort and paginate candidate items for a search request.
+///
+/// Facet filtering (state/price/seller) is expected to already have been
+/// applied upstream (e.g. via a DynamoDB filter expression); this only
+/// handles free-text ranking plus sort/offset/limit.
+pub fn execute(items: Vec<Item>, req: &SearchRequest) -> SearchResponse {
+    let terms = tokenize(&req.query);
+
+    let mut hits: Vec<SearchHit> = items
+        .into_iter()
+        .filter_map(|item| {
+            let score = if terms.is_empty() {
+                1.0
+            } else {
+                score_item(&item, &terms)
+            };
+            (score > 0.0).then_some(SearchHit { item, score })
+        })
+        .collect();
+
+    match req.sort {
+        SortKey::Relevance => hits.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.item.create_at.cmp(&a.item.create_at))
+        }),
+        SortKey::CreateAtDesc => hits.sort_by(|a, b| b.item.create_at.cmp(&a.item.create_at)),
+        SortKey::CreateAtAsc => hits.sort_by(|a, b| a.item.create_at.cmp(&b.item.create_at)),
+        SortKey::PriceAsc => hits.sort_by(|a, b| a.item.init_price.cmp(&b.item.init_price)),
+        SortKey::PriceDesc => hits.sort_by(|a, b| b.item.init_price.cmp(&a.item.init_price)),
+    }
+
+    let estimated_total_hits = hits.len();
+    let limit = req.limit.unwrap_or(DEFAULT_LIMIT);
+    let hits = hits.into_iter().skip(req.offset).take(limit).collect();
+
+    SearchResponse {
+        hits,
+        estimated_total_hits,
+        // Filled in by the caller, which times the whole `execute` call.
+        processing_time_ms: 0,
+    }
+}
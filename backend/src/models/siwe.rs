@@ -0,0 +1,125 @@
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use sha3::{Digest, Keccak256};
+
+/// The subset of EIP-4361 fields this crate needs out of a raw SIWE message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiweMessage {
+    pub domain: String,
+    pub address: String,
+    pub nonce: String,
+    pub issued_at: String,
+    pub expiration_time: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SiweError {
+    #[error("Malformed SIWE message: missing {0}")]
+    MissingField(&'static str),
+    #[error("SIWE message signature is malformed")]
+    MalformedSignature,
+    #[error("SIWE message signature does not recover to the claimed address")]
+    SignerMismatch,
+    #[error("SIWE nonce is missing, unknown, or expired")]
+    InvalidNonce,
+    #[error("SIWE message has expired")]
+    Expired,
+}
+
+impl SiweMessage {
+    /// Parse a raw EIP-4361 message. Only the fields this crate checks are
+    /// extracted; unrecognized lines are ignored.
+    pub fn parse(message: &str) -> Result<Self, SiweError> {
+        let mut lines = message.lines();
+
+        let domain = lines
+            .next()
+            .and_then(|l| l.strip_suffix(" wants you to sign in with your Ethereum account:"))
+            .ok_or(SiweError::MissingField("domain"))?
+            .to_string();
+
+        let address = lines
+            .next()
+            .filter(|l| !l.is_empty())
+            .ok_or(SiweError::MissingField("address"))?
+            .to_string();
+
+        let mut nonce = None;
+        let mut issued_at = None;
+        let mut expiration_time = None;
+        for line in lines {
+            if let Some(v) = line.strip_prefix("Nonce: ") {
+                nonce = Some(v.to_string());
+            } else if let Some(v) = line.strip_prefix("Issued At: ") {
+                issued_at = Some(v.to_string());
+            } else if let Some(v) = line.strip_prefix("Expiration Time: ") {
+                expiration_time = Some(v.to_string());
+            }
+        }
+
+        Ok(Self {
+            domain,
+            address,
+            nonce: nonce.ok_or(SiweError::MissingField("nonce"))?,
+            issued_at: issued_at.ok_or(SiweError::MissingField("issuedAt"))?,
+            expiration_time,
+        })
+    }
+}
+
+/// Recover the checksummed Ethereum address that produced `signature` over
+/// `message` via the EIP-191 `personal_sign` digest.
+pub fn recover_signer(message: &str, signature: &[u8]) -> Result<String, SiweError> {
+    if signature.len() != 65 {
+        return Err(SiweError::MalformedSignature);
+    }
+
+    let prefix = format!("\x19Ethereum Signed Message:\n{}", message.len());
+    let digest = Keccak256::new()
+        .chain_update(prefix.as_bytes())
+        .chain_update(message.as_bytes())
+        .finalize();
+
+    let sig =
+        Signature::from_slice(&signature[..64]).map_err(|_| SiweError::MalformedSignature)?;
+    let v = signature[64];
+    let recovery_id = RecoveryId::from_byte(if v >= 27 { v - 27 } else { v })
+        .ok_or(SiweError::MalformedSignature)?;
+
+    let verifying_key = VerifyingKey::recover_from_prehash(&digest, &sig, recovery_id)
+        .map_err(|_| SiweError::SignerMismatch)?;
+
+    Ok(to_checksum_address(&address_from_public_key(
+        &verifying_key,
+    )))
+}
+
+fn address_from_public_key(key: &VerifyingKey) -> [u8; 20] {
+    let point = key.to_encoded_point(false);
+    let hash = Keccak256::digest(&point.as_bytes()[1..]);
+    let mut addr = [0u8; 20];
+    addr.copy_from_slice(&hash[12..]);
+    addr
+}
+
+/// Render a raw 20-byte address as an EIP-55 checksummed hex string.
+fn to_checksum_address(addr: &[u8; 20]) -> String {
+    let hex_addr: String = addr.iter().map(|b| format!("{:02x}", b)).collect();
+    let hash = Keccak256::digest(hex_addr.as_bytes());
+
+    let mut out = String::with_capacity(42);
+    out.push_str("0x");
+    for (i, c) in hex_addr.chars().enumerate() {
+        if c.is_ascii_digit() {
+            out.push(c);
+        } else {
+            let byte = hash[i / 2];
+            let nibble = if i % 2 == 0 { byte >> 4 } else { byte & 0xf };
+            out.push(if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            });
+        }
+    }
+    out
+}
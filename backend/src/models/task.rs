@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+use utoipa::ToSchema;
+
+use super::item::ItemRef;
+
+/// Kind of work a `Task` performs.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    /// Settle an item whose auction has ended: if it has a winning bid, move
+    /// it to `Completed`, crediting the seller, debiting the winner's hold,
+    /// and recording the `Purchase` inline (see `SettleBid`); otherwise move
+    /// it to `Failed`.
+    CloseAuction,
+    /// Not processed on its own: `CloseAuction` creates the `Purchase` record
+    /// and credits the seller inline, and processing a `SettleBid` task fails.
+    SettleBid,
+    /// Release held funds back to every buyer whose bid on the item didn't win.
+    RefundBid,
+}
+
+/// Lifecycle status of a `Task`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Enqueued,
+    Processing,
+    Succeeded,
+    Failed,
+}
+
+/// A unit of background work driving auction settlement.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    /// Ulid, hash key
+    pub uid: Ulid,
+    /// What this task does.
+    pub kind: TaskKind,
+    /// Current lifecycle status.
+    pub status: TaskStatus,
+    /// Item this task operates on.
+    pub item_ref: ItemRef,
+    /// Unix timestamp the task was enqueued.
+    pub enqueued_at: u64,
+    /// Unix timestamp processing started, if any.
+    pub started_at: Option<u64>,
+    /// Unix timestamp processing finished, if any.
+    pub finished_at: Option<u64>,
+    /// Failure reason, set when `status == Failed`.
+    pub error: Option<String>,
+}
+
+impl Task {
+    pub fn new(kind: TaskKind, item_ref: ItemRef) -> Self {
+        Self {
+            uid: Ulid::new(),
+            kind,
+            status: TaskStatus::Enqueued,
+            item_ref,
+            enqueued_at: chrono::Local::now().timestamp_millis() as u64,
+            started_at: None,
+            finished_at: None,
+            error: None,
+        }
+    }
+}
@@ -30,10 +30,11 @@ impl fmt::Display for UserType {
 pub enum UserWrapper {
     Seller(Seller),
     Buyer(Buyer),
+    Admin(Admin),
 }
 
 impl UserWrapper {
-    pub fn create_claim(&self, exp: TimeDelta) -> Claim<'_> {
+    pub fn create_claim<'a>(&'a self, exp: TimeDelta, jti: &'a str, sid: &'a str) -> Claim<'a> {
         let now = chrono::Local::now();
         match self {
             UserWrapper::Buyer(user) => Claim {
@@ -45,6 +46,8 @@ impl UserWrapper {
                 iat: now.timestamp_millis() as u64,
                 exp: (now + exp).timestamp_millis() as u64,
                 aud: "auction-house-rs",
+                jti,
+                sid,
             },
             UserWrapper::Seller(user) => Claim {
                 id: &user.id,
@@ -55,14 +58,53 @@ impl UserWrapper {
                 iat: now.timestamp_millis() as u64,
                 exp: (now + exp).timestamp_millis() as u64,
                 aud: "auction-house-rs",
+                jti,
+                sid,
             },
+            UserWrapper::Admin(user) => Claim {
+                id: &user.id,
+                first_name: &user.first_name,
+                last_name: &user.last_name,
+                email: &user.email,
+                user_type: UserType::Admin,
+                iat: now.timestamp_millis() as u64,
+                exp: (now + exp).timestamp_millis() as u64,
+                aud: "auction-house-rs",
+                jti,
+                sid,
+            },
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            UserWrapper::Buyer(user) => &user.id,
+            UserWrapper::Seller(user) => &user.id,
+            UserWrapper::Admin(user) => &user.id,
         }
     }
 
-    pub fn password(&self) -> &str {
+    pub fn user_type(&self) -> UserType {
         match self {
-            UserWrapper::Buyer(user) => &user.password,
-            UserWrapper::Seller(user) => &user.password,
+            UserWrapper::Buyer(_) => UserType::Buyer,
+            UserWrapper::Seller(_) => UserType::Seller,
+            UserWrapper::Admin(_) => UserType::Admin,
+        }
+    }
+
+    pub fn opaque_registration(&self) -> &str {
+        match self {
+            UserWrapper::Buyer(user) => &user.opaque_registration,
+            UserWrapper::Seller(user) => &user.opaque_registration,
+            UserWrapper::Admin(user) => &user.opaque_registration,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        match self {
+            UserWrapper::Buyer(user) => user.is_active,
+            UserWrapper::Seller(user) => user.is_active,
+            UserWrapper::Admin(user) => user.is_active,
         }
     }
 
@@ -70,11 +112,16 @@ impl UserWrapper {
         let res = match self {
             UserWrapper::Buyer(user) => serde_dynamo::to_item(user)?,
             UserWrapper::Seller(user) => serde_dynamo::to_item(user)?,
+            UserWrapper::Admin(user) => serde_dynamo::to_item(user)?,
         };
         Ok(res)
     }
 
-    pub fn to_user_info(self, token: String) -> UserInfo {
+    /// Build the `UserInfo` returned by every sign-in endpoint. `token`/
+    /// `refresh_token` are `None` for a registration that's still pending
+    /// email verification (see `register_finish`), which never mints usable
+    /// credentials for an inactive account.
+    pub fn to_user_info(self, token: Option<String>, refresh_token: Option<String>) -> UserInfo {
         match self {
             UserWrapper::Buyer(user) => UserInfo {
                 id: user.id,
@@ -83,6 +130,7 @@ impl UserWrapper {
                 email: user.email,
                 user_type: UserType::Buyer,
                 token,
+                refresh_token,
             },
             UserWrapper::Seller(user) => UserInfo {
                 id: user.id,
@@ -91,6 +139,16 @@ impl UserWrapper {
                 email: user.email,
                 user_type: UserType::Seller,
                 token,
+                refresh_token,
+            },
+            UserWrapper::Admin(user) => UserInfo {
+                id: user.id,
+                first_name: user.first_name,
+                last_name: user.last_name,
+                email: user.email,
+                user_type: UserType::Admin,
+                token,
+                refresh_token,
             },
         }
     }
@@ -108,6 +166,12 @@ impl From<Seller> for UserWrapper {
     }
 }
 
+impl From<Admin> for UserWrapper {
+    fn from(value: Admin) -> Self {
+        Self::Admin(value)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserInfo {
@@ -121,8 +185,13 @@ pub struct UserInfo {
     pub email: String,
     /// User type of the returned user.
     pub user_type: UserType,
-    /// Signed JWT token.
-    pub token: String,
+    /// Signed JWT token. `None` if the account still needs email
+    /// verification (see `register_finish`); sign in via `/v1/login/*` once
+    /// verified to get one.
+    pub token: Option<String>,
+    /// Long-lived opaque refresh token; exchange it at `POST /v1/token/refresh`
+    /// for a fresh `token` once this one expires. `None` alongside `token`.
+    pub refresh_token: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
@@ -142,8 +211,8 @@ pub struct Seller {
     pub email: String,
     /// User fund
     pub fund: u64,
-    /// Password in scrypt.
-    pub password: String,
+    /// Hex-encoded, serialized OPAQUE `ServerRegistration` record.
+    pub opaque_registration: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
@@ -165,6 +234,28 @@ pub struct Buyer {
     pub fund: u64,
     /// User fund on hold
     pub fund_on_hold: u64,
-    /// Password in scrypt.
-    pub password: String,
+    /// Hex-encoded, serialized OPAQUE `ServerRegistration` record.
+    pub opaque_registration: String,
+    /// Lowercased wallet address, set for buyers registered via SIWE login.
+    #[serde(default)]
+    pub wallet_address: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Admin {
+    /// ID
+    pub id: String,
+    /// Create time, in unix timestamp
+    pub create_at: u64,
+    /// User is active
+    pub is_active: bool,
+    /// User first name
+    pub first_name: String,
+    /// User last name
+    pub last_name: String,
+    /// User Email
+    pub email: String,
+    /// Hex-encoded, serialized OPAQUE `ServerRegistration` record.
+    pub opaque_registration: String,
 }
@@ -0,0 +1,20 @@
+//! OPAQUE cipher suite configuration shared by registration and login.
+//!
+//! `register`/`login` already moved off scrypt onto this OPAQUE aPAKE
+//! (`routes::auth::register_start`/`register_finish`/`login_start`/
+//! `login_finish`), so the server never sees a password and persists only
+//! `opaque_registration` on `Buyer`/`Seller`. A later backlog request asking
+//! for the same migration is already satisfied by this module.
+
+use argon2::Argon2;
+use opaque_ke::{key_exchange::tripledh::TripleDh, CipherSuite, Ristretto255};
+
+/// Ristretto255 + tripleDH + Argon2, the suite this crate's OPAQUE flows use.
+pub struct DefaultCipherSuite;
+
+impl CipherSuite for DefaultCipherSuite {
+    type OprfCs = Ristretto255;
+    type KeGroup = Ristretto255;
+    type KeyExchange = TripleDh;
+    type Ksf = Argon2<'static>;
+}
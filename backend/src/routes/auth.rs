@@ -1,34 +1,177 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, env, sync::Arc};
 
-use aws_sdk_dynamodb::{types::AttributeValue, Client};
+use aws_sdk_dynamodb::{primitives::Blob, types::AttributeValue, Client};
 use axum::{
-    extract::{Json, State},
+    extract::{Json, Path, Query, State},
     http::StatusCode,
+    Extension,
 };
 use chrono::{Duration, TimeDelta};
-use scrypt::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Scrypt,
+use opaque_ke::{
+    rand::{rngs::OsRng, RngCore},
+    CredentialFinalization, CredentialRequest, RegistrationRequest, RegistrationUpload,
+    ServerLogin, ServerLoginStartParameters, ServerRegistration,
 };
+use ulid::Ulid;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
-    constants::{BUYER_TABLE, SELLER_TABLE},
+    constants::{
+        ACCESS_TOKEN_TABLE, ACCESS_TOKEN_USER_INDEX, ADMIN_TABLE, BUYER_TABLE, NONCE_TABLE,
+        NONCE_TTL_SECS, OAUTH_STATE_TABLE, OAUTH_STATE_TTL_SECS, OPAQUE_LOGIN_STATE_TABLE,
+        OPAQUE_LOGIN_STATE_TTL_SECS, PASSWORD_RESET_TABLE, PASSWORD_RESET_TOKEN_TTL_SECS,
+        SELLER_TABLE, SESSION_TABLE, SESSION_TTL_SECS, SESSION_USER_INDEX, VERIFICATION_TABLE,
+        VERIFICATION_TOKEN_TTL_SECS, WALLET_ADDRESS_INDEX,
+    },
     errors::HandlerError,
+    middlewares::ClientIp,
     models::{
-        auth::{LoginPayload, RegisterPayload},
-        user::{Buyer, Seller, UserInfo, UserType, UserWrapper},
+        auth::{
+            ClaimOwned, ForgotPasswordRequest, LoginFinishRequest, LoginStartRequest,
+            LoginStartResponse, NonceResponse, OAuthAuthorizeResponse, PasswordResetFinishRequest,
+            PasswordResetStartRequest, RefreshTokenRequest, RegistrationFinishRequest,
+            RegistrationStartRequest, RegistrationStartResponse, ResendVerificationRequest,
+            SessionInfo, WalletLoginPayload,
+        },
+        oauth::{build_authorize_url, exchange_code, fetch_email, generate_pkce_pair},
+        siwe::{recover_signer, SiweError, SiweMessage},
+        user::{Admin, Buyer, Seller, UserInfo, UserType, UserWrapper},
     },
+    opaque::DefaultCipherSuite,
     state::AppState,
-    utils::create_userid,
+    utils::{create_userid, hex_decode, hex_encode},
 };
 
-const TOKEN_EXPIRATION_DURATION: TimeDelta = Duration::hours(5);
+// Short-lived now that `/v1/token/refresh` exists to mint a fresh one without
+// forcing the user through login again; long-lived sessions live in `SESSION_TABLE`.
+const TOKEN_EXPIRATION_DURATION: TimeDelta = Duration::minutes(15);
 
 pub fn router() -> OpenApiRouter<Arc<AppState>> {
     OpenApiRouter::new()
-        .routes(routes!(register))
-        .routes(routes!(login))
+        .routes(routes!(register_start))
+        .routes(routes!(register_finish))
+        .routes(routes!(login_start))
+        .routes(routes!(login_finish))
+        .routes(routes!(nonce))
+        .routes(routes!(wallet_login))
+        .routes(routes!(verify_email))
+        .routes(routes!(resend_verification))
+        .routes(routes!(forgot_password))
+        .routes(routes!(password_reset_start))
+        .routes(routes!(password_reset_finish))
+        .routes(routes!(refresh_token))
+        .routes(routes!(oauth_authorize))
+        .routes(routes!(oauth_callback))
+}
+
+/// Routes that require an already-valid JWT, nested under `auth_middleware`.
+pub fn protected_router() -> OpenApiRouter<Arc<AppState>> {
+    OpenApiRouter::new()
+        .routes(routes!(logout))
+        .routes(routes!(revoke_all))
+        .routes(routes!(list_sessions))
+        .routes(routes!(delete_session))
+}
+
+/// Mint a JWT for `user` under session `sid`, recording it in `ACCESS_TOKEN_TABLE`
+/// so it can be revoked later.
+async fn issue_token(
+    client: &Client,
+    state: &AppState,
+    user: &UserWrapper,
+    sid: &str,
+) -> Result<String, HandlerError> {
+    let jti = Ulid::new().to_string();
+    let claim = user.create_claim(TOKEN_EXPIRATION_DURATION, &jti, sid);
+    let enc_key = &state.jwt.0;
+    let header = &state.jwt.2;
+    let token = jsonwebtoken::encode(header, &claim, enc_key)?;
+
+    client
+        .put_item()
+        .table_name(ACCESS_TOKEN_TABLE)
+        .item("tokenId", AttributeValue::S(jti))
+        .item("userId", AttributeValue::S(claim.id.to_string()))
+        .item("createdAt", AttributeValue::N(claim.iat.to_string()))
+        .item("valid", AttributeValue::Bool(true))
+        .send()
+        .await?;
+
+    Ok(token)
+}
+
+/// Mint a long-lived refresh token for `user` (the session id itself),
+/// recording it in `SESSION_TABLE` so `/v1/token/refresh` can later exchange
+/// it for a fresh JWT and `/v1/sessions` can list or revoke it.
+async fn issue_session(
+    client: &Client,
+    user: &UserWrapper,
+    source_ip: Option<&str>,
+) -> Result<String, HandlerError> {
+    let session_id = Ulid::new().to_string();
+    let issued_at = chrono::Local::now().timestamp() as u64;
+    let expire_at = issued_at + SESSION_TTL_SECS;
+
+    let mut req = client
+        .put_item()
+        .table_name(SESSION_TABLE)
+        .item("id", AttributeValue::S(session_id.clone()))
+        .item("userId", AttributeValue::S(user.id().to_string()))
+        .item("userType", serde_dynamo::to_attribute_value(user.user_type())?)
+        .item("issuedAt", AttributeValue::N(issued_at.to_string()))
+        .item("expireAt", AttributeValue::N(expire_at.to_string()))
+        .item("revoked", AttributeValue::Bool(false));
+    if let Some(ip) = source_ip {
+        req = req.item("sourceIp", AttributeValue::S(ip.to_string()));
+    }
+    req.send().await?;
+
+    Ok(session_id)
+}
+
+/// Mint both the refresh token (session) and the short-lived JWT for `user` —
+/// the pair returned by every endpoint that signs a user in.
+async fn issue_tokens(
+    client: &Client,
+    state: &AppState,
+    user: &UserWrapper,
+    source_ip: Option<&str>,
+) -> Result<(String, String), HandlerError> {
+    let session_id = issue_session(client, user, source_ip).await?;
+    let token = issue_token(client, state, user, &session_id).await?;
+    Ok((token, session_id))
+}
+
+/// Mint a single-use email-verification token for `user_id` and email it out
+/// via `state.mailer`. Used by both `register_finish` and `resend_verification`.
+async fn send_verification_email(
+    client: &Client,
+    state: &AppState,
+    user_id: &str,
+    user_type: UserType,
+    email: &str,
+) -> Result<(), HandlerError> {
+    let token = Ulid::new().to_string();
+    let expire_at = chrono::Local::now().timestamp() as u64 + VERIFICATION_TOKEN_TTL_SECS;
+
+    client
+        .put_item()
+        .table_name(VERIFICATION_TABLE)
+        .item("id", AttributeValue::S(token.clone()))
+        .item("userId", AttributeValue::S(user_id.to_string()))
+        .item("userType", serde_dynamo::to_attribute_value(user_type)?)
+        .item("expireAt", AttributeValue::N(expire_at.to_string()))
+        .send()
+        .await?;
+
+    state
+        .mailer
+        .send(
+            email,
+            "Verify your account",
+            &format!("Confirm your account by visiting: /v1/verify/{token}"),
+        )
+        .await
 }
 
 async fn get_user(
@@ -63,79 +206,152 @@ async fn get_user_full(
             let seller: Seller = serde_dynamo::from_item(user_item)?;
             Ok(UserWrapper::from(seller))
         }
-        UserType::Admin => unreachable!(),
+        UserType::Admin => {
+            let admin: Admin = serde_dynamo::from_item(user_item)?;
+            Ok(UserWrapper::from(admin))
+        }
+    }
+}
+
+fn user_table(user_type: UserType) -> &'static str {
+    match user_type {
+        UserType::Buyer => BUYER_TABLE,
+        UserType::Seller => SELLER_TABLE,
+        UserType::Admin => ADMIN_TABLE,
     }
 }
 
-/// Register user account.
+/// Start OPAQUE registration: blind the password client-side, get back the server's response.
+#[utoipa::path(
+    post,
+    path = "/v1/register/start",
+    tag = "Auth",
+    request_body(description = "Registration start", content = RegistrationStartRequest),
+    responses(
+        (status = OK, description = "Registration response issued", body = RegistrationStartResponse),
+        (status = BAD_REQUEST, description = "User already exists", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn register_start(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RegistrationStartRequest>,
+) -> Result<Json<RegistrationStartResponse>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let id = create_userid(&payload.email, payload.user_type);
+    let table = user_table(payload.user_type);
+
+    if get_user(&client, &id, table).await?.is_some() {
+        return Err(HandlerError::HandlerError(
+            StatusCode::BAD_REQUEST,
+            "User already exists".to_string(),
+        ));
+    }
+
+    let request_bytes = hex_decode(&payload.registration_request)?;
+    let request = RegistrationRequest::<DefaultCipherSuite>::deserialize(&request_bytes)?;
+
+    let result =
+        ServerRegistration::<DefaultCipherSuite>::start(&state.opaque_setup, request, id.as_bytes())?;
+
+    Ok(Json(RegistrationStartResponse {
+        registration_response: hex_encode(&result.message.serialize()),
+    }))
+}
+
+/// Finish OPAQUE registration, persisting the resulting server-side envelope.
+/// The account starts inactive pending email verification, so the returned
+/// `UserInfo` has no `token`/`refresh_token`; sign in via `/v1/login/*` after
+/// verifying.
 #[utoipa::path(
     post,
-    path = "/v1/register",
+    path = "/v1/register/finish",
     tag = "Auth",
-    request_body(description = "Register Info", content = RegisterPayload),
+    request_body(description = "Registration finish", content = RegistrationFinishRequest),
     responses(
-        (status = OK, description = "Register Success", body = UserInfo),
+        (status = OK, description = "Registered, pending email verification", body = UserInfo),
         (status = BAD_REQUEST, description = "User already exists", body = HandlerError),
         (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
     ),
 )]
-async fn register(
+async fn register_finish(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<RegisterPayload>,
+    Json(payload): Json<RegistrationFinishRequest>,
 ) -> Result<Json<UserInfo>, HandlerError> {
     let client = Client::new(&state.aws_config);
     let id = create_userid(&payload.email, payload.user_type);
-    let table = match payload.user_type {
-        UserType::Buyer => BUYER_TABLE,
-        UserType::Seller => SELLER_TABLE,
-        UserType::Admin => unreachable!(),
-    };
+    let table = user_table(payload.user_type);
 
-    // 1. Check user existance.
-    let get_user_resp = get_user(&client, &id, table).await?;
-    if get_user_resp.is_some() {
+    if get_user(&client, &id, table).await?.is_some() {
         return Err(HandlerError::HandlerError(
             StatusCode::BAD_REQUEST,
             "User already exists".to_string(),
         ));
     }
 
-    // 2. Create password hash.
-    let salt = SaltString::generate(&mut OsRng);
-    let phash = Scrypt
-        .hash_password(payload.password.as_bytes(), &salt)?
-        .to_string();
+    // Unlike Buyer/Seller, Admin isn't open signup: the caller must present the
+    // bootstrap secret operators hand out as an invite, so an attacker can't
+    // just register themselves an admin account.
+    if payload.user_type == UserType::Admin {
+        let invite_secret = env::var("ADMIN_INVITE_SECRET").map_err(|_| {
+            HandlerError::HandlerError(
+                StatusCode::FORBIDDEN,
+                "Admin registration is not open".to_string(),
+            )
+        })?;
+        if payload.invite_secret.as_deref() != Some(invite_secret.as_str()) {
+            return Err(HandlerError::HandlerError(
+                StatusCode::FORBIDDEN,
+                "Invalid admin invite secret".to_string(),
+            ));
+        }
+    }
+
+    let upload_bytes = hex_decode(&payload.registration_upload)?;
+    let upload = RegistrationUpload::<DefaultCipherSuite>::deserialize(&upload_bytes)?;
+    let registration = ServerRegistration::<DefaultCipherSuite>::finish(upload);
+    let opaque_registration = hex_encode(&registration.serialize());
 
-    // 3. Create user.
     let now = chrono::Local::now();
     let current = now.timestamp_millis() as u64;
     let user = match payload.user_type {
         UserType::Buyer => UserWrapper::from(Buyer {
             id: id.clone(),
             create_at: current,
-            is_active: true,
+            // Gated on email confirmation; see `verify_email`.
+            is_active: false,
             first_name: payload.first_name.clone(),
             last_name: payload.last_name.clone(),
             email: payload.email.clone(),
             fund: 0,
             fund_on_hold: 0,
-            password: phash,
+            opaque_registration,
+            wallet_address: None,
         }),
         UserType::Seller => UserWrapper::from(Seller {
             id: id.clone(),
             create_at: current,
-            is_active: true,
+            // Gated on email confirmation; see `verify_email`.
+            is_active: false,
             first_name: payload.first_name.clone(),
             last_name: payload.last_name.clone(),
             email: payload.email.clone(),
             fund: 0,
-            password: phash,
+            opaque_registration,
+        }),
+        UserType::Admin => UserWrapper::from(Admin {
+            id: id.clone(),
+            create_at: current,
+            // Gated on email confirmation; see `verify_email`.
+            is_active: false,
+            first_name: payload.first_name.clone(),
+            last_name: payload.last_name.clone(),
+            email: payload.email.clone(),
+            opaque_registration,
         }),
-        UserType::Admin => unreachable!(),
     };
     let user_item = user.clone().to_item()?;
 
-    // 4. Write item.
     client
         .put_item()
         .table_name(table)
@@ -143,55 +359,1126 @@ async fn register(
         .send()
         .await?;
 
-    // 5. Sign JWT token.
-    let enc_key = &state.jwt.0;
-    let header = &state.jwt.2;
-    let claim = user.create_claim(TOKEN_EXPIRATION_DURATION);
+    send_verification_email(&client, &state, &id, payload.user_type, &payload.email).await?;
 
-    let token = jsonwebtoken::encode(header, &claim, enc_key)?;
+    // Unlike every other sign-in endpoint, a fresh registration is still
+    // inactive (see `is_active: false` above) until `verify_email` runs, so
+    // it gets no token/refresh_token here — minting one would hand out a
+    // working session before email verification ever happened.
+    Ok(Json(user.to_user_info(None, None)))
+}
 
-    Ok(Json(user.to_user_info(token)))
+/// Start OPAQUE login: return a credential response and stash server-side login state.
+#[utoipa::path(
+    post,
+    path = "/v1/login/start",
+    tag = "Auth",
+    request_body(description = "Login start", content = LoginStartRequest),
+    responses(
+        (status = OK, description = "Credential response issued", body = LoginStartResponse),
+        (status = NOT_FOUND, description = "User not found", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn login_start(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<LoginStartRequest>,
+) -> Result<Json<LoginStartResponse>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let id = create_userid(&payload.email, payload.user_type);
+    let table = user_table(payload.user_type);
+
+    let user = get_user_full(&client, &id, table, payload.user_type).await?;
+    let registration_bytes = hex_decode(user.opaque_registration())?;
+    let registration = ServerRegistration::<DefaultCipherSuite>::deserialize(&registration_bytes)?;
+
+    let request_bytes = hex_decode(&payload.credential_request)?;
+    let credential_request = CredentialRequest::<DefaultCipherSuite>::deserialize(&request_bytes)?;
+
+    let result = ServerLogin::start(
+        &mut OsRng,
+        &state.opaque_setup,
+        Some(registration),
+        credential_request,
+        id.as_bytes(),
+        ServerLoginStartParameters::default(),
+    )?;
+
+    let expire_at = chrono::Local::now().timestamp() as u64 + OPAQUE_LOGIN_STATE_TTL_SECS;
+    client
+        .put_item()
+        .table_name(OPAQUE_LOGIN_STATE_TABLE)
+        .item("id", AttributeValue::S(id))
+        .item(
+            "state",
+            AttributeValue::B(Blob::new(result.state.serialize())),
+        )
+        .item("expireAt", AttributeValue::N(expire_at.to_string()))
+        .send()
+        .await?;
+
+    Ok(Json(LoginStartResponse {
+        credential_response: hex_encode(&result.message.serialize()),
+    }))
 }
 
-/// User Login
+/// Finish OPAQUE login, verifying the client's proof and issuing a JWT.
 #[utoipa::path(
     post,
-    path = "/v1/login",
+    path = "/v1/login/finish",
     tag = "Auth",
-    request_body(description = "Register Info", content = LoginPayload),
+    request_body(description = "Login finish", content = LoginFinishRequest),
     responses(
         (status = OK, description = "Login Success", body = UserInfo),
-        (status = BAD_REQUEST, description = "Wrong password or malformed password hash", body = HandlerError),
+        (status = UNAUTHORIZED, description = "Bad proof or expired login attempt", body = HandlerError),
+        (status = FORBIDDEN, description = "Account email has not been verified", body = HandlerError),
         (status = NOT_FOUND, description = "User not found", body = HandlerError),
         (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
     ),
 )]
-async fn login(
+async fn login_finish(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<LoginPayload>,
+    client_ip: Option<Extension<ClientIp>>,
+    Json(payload): Json<LoginFinishRequest>,
 ) -> Result<Json<UserInfo>, HandlerError> {
     let client = Client::new(&state.aws_config);
     let id = create_userid(&payload.email, payload.user_type);
-    let table = match payload.user_type {
-        UserType::Buyer => BUYER_TABLE,
-        UserType::Seller => SELLER_TABLE,
-        UserType::Admin => unreachable!(),
+    let table = user_table(payload.user_type);
+
+    let state_resp = client
+        .get_item()
+        .table_name(OPAQUE_LOGIN_STATE_TABLE)
+        .key("id", AttributeValue::S(id.clone()))
+        .send()
+        .await?;
+    let state_item = state_resp.item.ok_or_else(|| {
+        HandlerError::HandlerError(
+            StatusCode::UNAUTHORIZED,
+            "No login attempt in progress".to_string(),
+        )
+    })?;
+
+    let expire_at: u64 = state_item
+        .get("expireAt")
+        .and_then(|v| v.as_n().ok())
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| {
+            HandlerError::HandlerError(StatusCode::UNAUTHORIZED, "Login attempt expired".to_string())
+        })?;
+    if chrono::Local::now().timestamp() as u64 >= expire_at {
+        return Err(HandlerError::HandlerError(
+            StatusCode::UNAUTHORIZED,
+            "Login attempt expired".to_string(),
+        ));
+    }
+
+    let login_state_bytes = state_item
+        .get("state")
+        .and_then(|v| v.as_b().ok())
+        .map(|b| b.as_ref().to_vec())
+        .ok_or_else(|| {
+            HandlerError::HandlerError(StatusCode::UNAUTHORIZED, "Login attempt expired".to_string())
+        })?;
+    let server_login = ServerLogin::<DefaultCipherSuite>::deserialize(&login_state_bytes)?;
+
+    let finalization_bytes = hex_decode(&payload.credential_finalization)?;
+    let finalization = CredentialFinalization::<DefaultCipherSuite>::deserialize(&finalization_bytes)?;
+    server_login.finish(finalization)?;
+
+    // Single-use: consume the login attempt now that the proof has been verified.
+    client
+        .delete_item()
+        .table_name(OPAQUE_LOGIN_STATE_TABLE)
+        .key("id", AttributeValue::S(id.clone()))
+        .send()
+        .await?;
+
+    let user = get_user_full(&client, &id, table, payload.user_type).await?;
+    if !user.is_active() {
+        return Err(HandlerError::AccountNotVerified);
+    }
+    let source_ip = client_ip.map(|Extension(ClientIp(ip))| ip);
+    let (token, refresh_token) = issue_tokens(&client, &state, &user, source_ip.as_deref()).await?;
+
+    Ok(Json(user.to_user_info(Some(token), Some(refresh_token))))
+}
+
+#[derive(serde::Deserialize)]
+struct NonceQuery {
+    address: String,
+}
+
+/// Mint a short-lived SIWE login nonce for a wallet address.
+#[utoipa::path(
+    get,
+    path = "/v1/auth/nonce",
+    tag = "Auth",
+    params(
+        ("address" = String, Query, description = "Wallet address to mint a nonce for"),
+    ),
+    responses(
+        (status = OK, description = "Nonce minted", body = NonceResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn nonce(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<NonceQuery>,
+) -> Result<Json<NonceResponse>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+
+    let nonce = generate_nonce();
+    let expire_at = chrono::Local::now().timestamp() as u64 + NONCE_TTL_SECS;
+
+    client
+        .put_item()
+        .table_name(NONCE_TABLE)
+        .item(
+            "address",
+            AttributeValue::S(query.address.to_lowercase()),
+        )
+        .item("nonce", AttributeValue::S(nonce.clone()))
+        .item("expireAt", AttributeValue::N(expire_at.to_string()))
+        .send()
+        .await?;
+
+    Ok(Json(NonceResponse { nonce }))
+}
+
+/// Login (registering on first use) a buyer by verifying a signed SIWE message.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/wallet-login",
+    tag = "Auth",
+    request_body(description = "Wallet Login Info", content = WalletLoginPayload),
+    responses(
+        (status = OK, description = "Login Success", body = UserInfo),
+        (status = BAD_REQUEST, description = "Malformed message, bad signature, or bad/expired nonce", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn wallet_login(
+    State(state): State<Arc<AppState>>,
+    client_ip: Option<Extension<ClientIp>>,
+    Json(payload): Json<WalletLoginPayload>,
+) -> Result<Json<UserInfo>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+
+    let parsed = SiweMessage::parse(&payload.message)?;
+    let signature = decode_hex_signature(&payload.signature)?;
+    let recovered = recover_signer(&payload.message, &signature)?;
+
+    if recovered.to_lowercase() != parsed.address.to_lowercase() {
+        return Err(SiweError::SignerMismatch.into());
+    }
+    let address = recovered.to_lowercase();
+
+    if let Some(exp) = &parsed.expiration_time {
+        let expiry = chrono::DateTime::parse_from_rfc3339(exp)
+            .map_err(|_| SiweError::MalformedSignature)?;
+        if chrono::Utc::now() >= expiry {
+            return Err(SiweError::Expired.into());
+        }
+    }
+
+    // 1. Verify the nonce was minted for this address and hasn't expired.
+    let nonce_resp = client
+        .get_item()
+        .table_name(NONCE_TABLE)
+        .key("address", AttributeValue::S(address.clone()))
+        .send()
+        .await?;
+    let nonce_item = nonce_resp.item.ok_or(SiweError::InvalidNonce)?;
+
+    let stored_nonce = nonce_item
+        .get("nonce")
+        .and_then(|v| v.as_s().ok())
+        .cloned()
+        .ok_or(SiweError::InvalidNonce)?;
+    let expire_at: u64 = nonce_item
+        .get("expireAt")
+        .and_then(|v| v.as_n().ok())
+        .and_then(|n| n.parse().ok())
+        .ok_or(SiweError::InvalidNonce)?;
+
+    let now = chrono::Local::now().timestamp() as u64;
+    if stored_nonce != parsed.nonce || now >= expire_at {
+        return Err(SiweError::InvalidNonce.into());
+    }
+
+    // 2. Consume the nonce so it can't be replayed.
+    client
+        .delete_item()
+        .table_name(NONCE_TABLE)
+        .key("address", AttributeValue::S(address.clone()))
+        .send()
+        .await?;
+
+    // 3. Find or create the buyer row for this wallet.
+    let buyer = match find_buyer_by_wallet(&client, &address).await? {
+        Some(buyer) => buyer,
+        None => {
+            let buyer = Buyer {
+                id: create_userid(&address, UserType::Buyer),
+                create_at: chrono::Local::now().timestamp_millis() as u64,
+                is_active: true,
+                first_name: String::new(),
+                last_name: String::new(),
+                email: String::new(),
+                fund: 0,
+                fund_on_hold: 0,
+                // Wallet-only buyers never go through the password/OPAQUE login path;
+                // this is an unusable placeholder, not a credential.
+                opaque_registration: Ulid::new().to_string(),
+                wallet_address: Some(address.clone()),
+            };
+            client
+                .put_item()
+                .table_name(BUYER_TABLE)
+                .set_item(Some(serde_dynamo::to_item(buyer.clone())?))
+                .send()
+                .await?;
+            buyer
+        }
     };
 
-    // 1. Check if user exists
+    let user = UserWrapper::from(buyer);
+    let source_ip = client_ip.map(|Extension(ClientIp(ip))| ip);
+    let (token, refresh_token) = issue_tokens(&client, &state, &user, source_ip.as_deref()).await?;
+
+    Ok(Json(user.to_user_info(Some(token), Some(refresh_token))))
+}
+
+/// Confirm a registration by its emailed verification token, activating the account.
+#[utoipa::path(
+    get,
+    path = "/v1/verify/{token}",
+    tag = "Auth",
+    params(
+        ("token" = String, Path, description = "Verification token from the confirmation email"),
+    ),
+    responses(
+        (status = OK, description = "Account activated"),
+        (status = BAD_REQUEST, description = "Invalid or expired verification token", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn verify_email(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<(), HandlerError> {
+    let client = Client::new(&state.aws_config);
+
+    let resp = client
+        .get_item()
+        .table_name(VERIFICATION_TABLE)
+        .key("id", AttributeValue::S(token.clone()))
+        .send()
+        .await?;
+    let item = resp.item.ok_or_else(invalid_verification_token)?;
+
+    let expire_at: u64 = item
+        .get("expireAt")
+        .and_then(|v| v.as_n().ok())
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(invalid_verification_token)?;
+    if chrono::Local::now().timestamp() as u64 >= expire_at {
+        return Err(invalid_verification_token());
+    }
+
+    let user_id = item
+        .get("userId")
+        .and_then(|v| v.as_s().ok())
+        .cloned()
+        .ok_or_else(invalid_verification_token)?;
+    let user_type: UserType = item
+        .get("userType")
+        .cloned()
+        .ok_or_else(invalid_verification_token)
+        .and_then(|v| serde_dynamo::from_attribute_value(v).map_err(HandlerError::from))?;
+
+    client
+        .update_item()
+        .table_name(user_table(user_type))
+        .key("id", AttributeValue::S(user_id))
+        .update_expression("SET isActive = :active")
+        .expression_attribute_values(":active", AttributeValue::Bool(true))
+        .send()
+        .await?;
+
+    client
+        .delete_item()
+        .table_name(VERIFICATION_TABLE)
+        .key("id", AttributeValue::S(token))
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+fn invalid_verification_token() -> HandlerError {
+    HandlerError::HandlerError(
+        StatusCode::BAD_REQUEST,
+        "Invalid or expired verification token".to_string(),
+    )
+}
+
+/// Re-send the verification email for an account that hasn't confirmed yet.
+#[utoipa::path(
+    post,
+    path = "/v1/verify/resend",
+    tag = "Auth",
+    request_body(description = "Resend verification", content = ResendVerificationRequest),
+    responses(
+        (status = OK, description = "Verification email (re)sent, or account was already active"),
+        (status = NOT_FOUND, description = "User not found", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn resend_verification(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ResendVerificationRequest>,
+) -> Result<(), HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let id = create_userid(&payload.email, payload.user_type);
+    let table = user_table(payload.user_type);
+
     let user = get_user_full(&client, &id, table, payload.user_type).await?;
+    if user.is_active() {
+        return Ok(());
+    }
+
+    send_verification_email(&client, &state, &id, payload.user_type, &payload.email).await
+}
+
+/// Look up a password-reset token's backing row, rejecting missing/expired ones
+/// with the same response whether the row never existed or just lapsed, so a
+/// caller can't use timing/shape to learn which.
+async fn get_reset_token(
+    client: &Client,
+    token: &str,
+) -> Result<HashMap<String, AttributeValue>, HandlerError> {
+    let resp = client
+        .get_item()
+        .table_name(PASSWORD_RESET_TABLE)
+        .key("id", AttributeValue::S(token.to_string()))
+        .send()
+        .await?;
+    let item = resp.item.ok_or_else(invalid_reset_token)?;
 
-    // 2. verify hash
-    let phash = PasswordHash::new(user.password())?;
+    let expire_at: u64 = item
+        .get("expireAt")
+        .and_then(|v| v.as_n().ok())
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(invalid_reset_token)?;
+    if chrono::Local::now().timestamp() as u64 >= expire_at {
+        return Err(invalid_reset_token());
+    }
 
-    Scrypt.verify_password(payload.password.as_bytes(), &phash)?;
+    Ok(item)
+}
 
-    // 3. Sign JWT token
-    let enc_key = &state.jwt.0;
-    let header = &state.jwt.2;
-    let claim = user.create_claim(TOKEN_EXPIRATION_DURATION);
+fn invalid_reset_token() -> HandlerError {
+    HandlerError::HandlerError(
+        StatusCode::BAD_REQUEST,
+        "Invalid or expired password reset token".to_string(),
+    )
+}
 
-    let token = jsonwebtoken::encode(header, &claim, enc_key)?;
+fn reset_token_user(item: &HashMap<String, AttributeValue>) -> Result<(String, UserType), HandlerError> {
+    let user_id = item
+        .get("userId")
+        .and_then(|v| v.as_s().ok())
+        .cloned()
+        .ok_or_else(invalid_reset_token)?;
+    let user_type: UserType = item
+        .get("userType")
+        .cloned()
+        .ok_or_else(invalid_reset_token)
+        .and_then(|v| serde_dynamo::from_attribute_value(v).map_err(HandlerError::from))?;
+    Ok((user_id, user_type))
+}
+
+/// Request a password reset. Always returns `200`, whether or not the
+/// account exists, so this can't be used to enumerate registered emails.
+#[utoipa::path(
+    post,
+    path = "/v1/password/forgot",
+    tag = "Auth",
+    request_body(description = "Forgot password", content = ForgotPasswordRequest),
+    responses(
+        (status = OK, description = "Reset email sent if the account exists"),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn forgot_password(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ForgotPasswordRequest>,
+) -> Result<(), HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let id = create_userid(&payload.email, payload.user_type);
+    let table = user_table(payload.user_type);
+
+    if get_user(&client, &id, table).await?.is_none() {
+        return Ok(());
+    }
+
+    let token = Ulid::new().to_string();
+    let expire_at = chrono::Local::now().timestamp() as u64 + PASSWORD_RESET_TOKEN_TTL_SECS;
 
-    Ok(Json(user.to_user_info(token)))
+    client
+        .put_item()
+        .table_name(PASSWORD_RESET_TABLE)
+        .item("id", AttributeValue::S(token.clone()))
+        .item("userId", AttributeValue::S(id))
+        .item("userType", serde_dynamo::to_attribute_value(payload.user_type)?)
+        .item("expireAt", AttributeValue::N(expire_at.to_string()))
+        .send()
+        .await?;
+
+    state
+        .mailer
+        .send(
+            &payload.email,
+            "Reset your password",
+            &format!("Reset your password with this token: {token}"),
+        )
+        .await
+}
+
+/// Start a password reset: exchange a reset token and a blinded new password
+/// for an OPAQUE registration response, exactly like `register_start` but
+/// scoped to an already-registered account.
+#[utoipa::path(
+    post,
+    path = "/v1/password/reset/start",
+    tag = "Auth",
+    request_body(description = "Password reset start", content = PasswordResetStartRequest),
+    responses(
+        (status = OK, description = "Registration response issued", body = RegistrationStartResponse),
+        (status = BAD_REQUEST, description = "Invalid or expired password reset token", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn password_reset_start(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<PasswordResetStartRequest>,
+) -> Result<Json<RegistrationStartResponse>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let reset_item = get_reset_token(&client, &payload.token).await?;
+    let (user_id, _) = reset_token_user(&reset_item)?;
+
+    let request_bytes = hex_decode(&payload.registration_request)?;
+    let request = RegistrationRequest::<DefaultCipherSuite>::deserialize(&request_bytes)?;
+
+    let result = ServerRegistration::<DefaultCipherSuite>::start(
+        &state.opaque_setup,
+        request,
+        user_id.as_bytes(),
+    )?;
+
+    Ok(Json(RegistrationStartResponse {
+        registration_response: hex_encode(&result.message.serialize()),
+    }))
+}
+
+/// Finish a password reset, overwriting the account's OPAQUE registration
+/// and consuming the token, then signing the user straight back in.
+#[utoipa::path(
+    post,
+    path = "/v1/password/reset/finish",
+    tag = "Auth",
+    request_body(description = "Password reset finish", content = PasswordResetFinishRequest),
+    responses(
+        (status = OK, description = "Password reset, user logged in", body = UserInfo),
+        (status = BAD_REQUEST, description = "Invalid or expired password reset token", body = HandlerError),
+        (status = FORBIDDEN, description = "Account email has not been verified", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn password_reset_finish(
+    State(state): State<Arc<AppState>>,
+    client_ip: Option<Extension<ClientIp>>,
+    Json(payload): Json<PasswordResetFinishRequest>,
+) -> Result<Json<UserInfo>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let reset_item = get_reset_token(&client, &payload.token).await?;
+    let (user_id, user_type) = reset_token_user(&reset_item)?;
+    let table = user_table(user_type);
+
+    let upload_bytes = hex_decode(&payload.registration_upload)?;
+    let upload = RegistrationUpload::<DefaultCipherSuite>::deserialize(&upload_bytes)?;
+    let registration = ServerRegistration::<DefaultCipherSuite>::finish(upload);
+    let opaque_registration = hex_encode(&registration.serialize());
+
+    client
+        .update_item()
+        .table_name(table)
+        .key("id", AttributeValue::S(user_id.clone()))
+        .update_expression("SET opaqueRegistration = :reg")
+        .expression_attribute_values(":reg", AttributeValue::S(opaque_registration))
+        .send()
+        .await?;
+
+    client
+        .delete_item()
+        .table_name(PASSWORD_RESET_TABLE)
+        .key("id", AttributeValue::S(payload.token))
+        .send()
+        .await?;
+
+    let user = get_user_full(&client, &user_id, table, user_type).await?;
+    if !user.is_active() {
+        return Err(HandlerError::AccountNotVerified);
+    }
+    let source_ip = client_ip.map(|Extension(ClientIp(ip))| ip);
+    let (token, refresh_token) = issue_tokens(&client, &state, &user, source_ip.as_deref()).await?;
+
+    Ok(Json(user.to_user_info(Some(token), Some(refresh_token))))
+}
+
+struct Session {
+    user_id: String,
+    user_type: UserType,
+}
+
+/// Look up a session by its refresh token, rejecting missing, expired, or
+/// revoked ones with the same response so a caller can't tell which.
+async fn get_session(client: &Client, refresh_token: &str) -> Result<Session, HandlerError> {
+    let resp = client
+        .get_item()
+        .table_name(SESSION_TABLE)
+        .key("id", AttributeValue::S(refresh_token.to_string()))
+        .send()
+        .await?;
+    let item = resp.item.ok_or_else(invalid_session)?;
+
+    let revoked = item
+        .get("revoked")
+        .and_then(|v| v.as_bool().ok())
+        .copied()
+        .unwrap_or(false);
+    let expire_at: u64 = item
+        .get("expireAt")
+        .and_then(|v| v.as_n().ok())
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(invalid_session)?;
+    if revoked || chrono::Local::now().timestamp() as u64 >= expire_at {
+        return Err(invalid_session());
+    }
+
+    let user_id = item
+        .get("userId")
+        .and_then(|v| v.as_s().ok())
+        .cloned()
+        .ok_or_else(invalid_session)?;
+    let user_type: UserType = item
+        .get("userType")
+        .cloned()
+        .ok_or_else(invalid_session)
+        .and_then(|v| serde_dynamo::from_attribute_value(v).map_err(HandlerError::from))?;
+
+    Ok(Session { user_id, user_type })
+}
+
+fn invalid_session() -> HandlerError {
+    HandlerError::HandlerError(
+        StatusCode::UNAUTHORIZED,
+        "Invalid, expired, or revoked refresh token".to_string(),
+    )
+}
+
+async fn revoke_session(client: &Client, session_id: &str) -> Result<(), HandlerError> {
+    client
+        .update_item()
+        .table_name(SESSION_TABLE)
+        .key("id", AttributeValue::S(session_id.to_string()))
+        .update_expression("SET revoked = :revoked")
+        .expression_attribute_values(":revoked", AttributeValue::Bool(true))
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Exchange a refresh token for a fresh, short-lived JWT, without re-running
+/// the OPAQUE login flow. The refresh token itself is unchanged and can be
+/// reused until it expires or the session is revoked.
+#[utoipa::path(
+    post,
+    path = "/v1/token/refresh",
+    tag = "Auth",
+    request_body(description = "Refresh token", content = RefreshTokenRequest),
+    responses(
+        (status = OK, description = "Fresh access token issued", body = UserInfo),
+        (status = UNAUTHORIZED, description = "Refresh token invalid, expired, or revoked", body = HandlerError),
+        (status = FORBIDDEN, description = "Account email has not been verified", body = HandlerError),
+        (status = NOT_FOUND, description = "User not found", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn refresh_token(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RefreshTokenRequest>,
+) -> Result<Json<UserInfo>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let session = get_session(&client, &payload.refresh_token).await?;
+    let table = user_table(session.user_type);
+
+    let user = get_user_full(&client, &session.user_id, table, session.user_type).await?;
+    if !user.is_active() {
+        return Err(HandlerError::AccountNotVerified);
+    }
+    let token = issue_token(&client, &state, &user, &payload.refresh_token).await?;
+
+    Ok(Json(user.to_user_info(Some(token), Some(payload.refresh_token))))
+}
+
+#[derive(serde::Deserialize)]
+struct OAuthAuthorizeQuery {
+    user_type: UserType,
+}
+
+/// Build `provider`'s authorization URL and stash the PKCE verifier and CSRF
+/// state server-side until the callback redeems them.
+#[utoipa::path(
+    get,
+    path = "/v1/oauth/{provider}/authorize",
+    tag = "Auth",
+    params(
+        ("provider" = String, Path, description = "Configured OAuth2 provider name"),
+        ("user_type" = UserType, Query, description = "Account type to link or provision on callback"),
+    ),
+    responses(
+        (status = OK, description = "Authorization URL issued", body = OAuthAuthorizeResponse),
+        (status = NOT_FOUND, description = "Unknown OAuth provider", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn oauth_authorize(
+    State(state): State<Arc<AppState>>,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthAuthorizeQuery>,
+) -> Result<Json<OAuthAuthorizeResponse>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let config = state
+        .oauth_providers
+        .get(&provider)
+        .ok_or_else(HandlerError::not_found)?;
+
+    let pkce = generate_pkce_pair();
+    let csrf_state = Ulid::new().to_string();
+    let expire_at = chrono::Local::now().timestamp() as u64 + OAUTH_STATE_TTL_SECS;
+
+    client
+        .put_item()
+        .table_name(OAUTH_STATE_TABLE)
+        .item("id", AttributeValue::S(csrf_state.clone()))
+        .item("codeVerifier", AttributeValue::S(pkce.verifier))
+        .item("userType", serde_dynamo::to_attribute_value(query.user_type)?)
+        .item("expireAt", AttributeValue::N(expire_at.to_string()))
+        .send()
+        .await?;
+
+    let authorize_url = build_authorize_url(config, &csrf_state, &pkce.challenge)?;
+
+    Ok(Json(OAuthAuthorizeResponse { authorize_url }))
+}
+
+/// Look up an in-flight OAuth2 attempt by its CSRF state, rejecting missing
+/// or expired ones with the same response so a caller can't tell which.
+async fn get_oauth_state(
+    client: &Client,
+    state: &str,
+) -> Result<(String, UserType), HandlerError> {
+    let resp = client
+        .get_item()
+        .table_name(OAUTH_STATE_TABLE)
+        .key("id", AttributeValue::S(state.to_string()))
+        .send()
+        .await?;
+    let item = resp.item.ok_or_else(invalid_oauth_state)?;
+
+    let expire_at: u64 = item
+        .get("expireAt")
+        .and_then(|v| v.as_n().ok())
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(invalid_oauth_state)?;
+    if chrono::Local::now().timestamp() as u64 >= expire_at {
+        return Err(invalid_oauth_state());
+    }
+
+    let code_verifier = item
+        .get("codeVerifier")
+        .and_then(|v| v.as_s().ok())
+        .cloned()
+        .ok_or_else(invalid_oauth_state)?;
+    let user_type: UserType = item
+        .get("userType")
+        .cloned()
+        .ok_or_else(invalid_oauth_state)
+        .and_then(|v| serde_dynamo::from_attribute_value(v).map_err(HandlerError::from))?;
+
+    Ok((code_verifier, user_type))
+}
+
+fn invalid_oauth_state() -> HandlerError {
+    HandlerError::HandlerError(
+        StatusCode::BAD_REQUEST,
+        "Invalid or expired OAuth authorization attempt".to_string(),
+    )
+}
+
+#[derive(serde::Deserialize)]
+struct OAuthCallbackQuery {
+    code: String,
+    state: String,
+}
+
+/// Redeem an authorization code: exchange it for an access token, fetch the
+/// provider's email, then either link to an existing `Buyer`/`Seller` by
+/// `create_userid(email, user_type)` or provision a new, external-only one
+/// (same unusable-placeholder `opaque_registration` as `wallet_login`).
+#[utoipa::path(
+    get,
+    path = "/v1/oauth/{provider}/callback",
+    tag = "Auth",
+    params(
+        ("provider" = String, Path, description = "Configured OAuth2 provider name"),
+        ("code" = String, Query, description = "Authorization code issued by the provider"),
+        ("state" = String, Query, description = "CSRF state returned from `oauth_authorize`"),
+    ),
+    responses(
+        (status = OK, description = "Login Success", body = UserInfo),
+        (status = BAD_REQUEST, description = "Invalid/expired attempt, or provider returned no email", body = HandlerError),
+        (status = NOT_FOUND, description = "Unknown OAuth provider", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn oauth_callback(
+    State(state): State<Arc<AppState>>,
+    client_ip: Option<Extension<ClientIp>>,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthCallbackQuery>,
+) -> Result<Json<UserInfo>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let config = state
+        .oauth_providers
+        .get(&provider)
+        .ok_or_else(HandlerError::not_found)?;
+
+    let (code_verifier, user_type) = get_oauth_state(&client, &query.state).await?;
+    if user_type == UserType::Admin {
+        return Err(HandlerError::HandlerError(
+            StatusCode::BAD_REQUEST,
+            "OAuth login is not available for admin accounts".to_string(),
+        ));
+    }
+    // Single-use: consume the attempt now that its verifier has been retrieved.
+    client
+        .delete_item()
+        .table_name(OAUTH_STATE_TABLE)
+        .key("id", AttributeValue::S(query.state))
+        .send()
+        .await?;
+
+    let http = reqwest::Client::new();
+    let access_token = exchange_code(&http, config, &query.code, &code_verifier).await?;
+    let email = fetch_email(&http, config, &access_token).await?;
+
+    let id = create_userid(&email, user_type);
+    let table = user_table(user_type);
+    let user = match get_user(&client, &id, table).await? {
+        Some(item) => oauth_user_from_item(user_type, item)?,
+        None => {
+            let user = provision_oauth_user(user_type, &id, &email);
+            client
+                .put_item()
+                .table_name(table)
+                .set_item(Some(user.clone().to_item()?))
+                .send()
+                .await?;
+            user
+        }
+    };
+
+    let source_ip = client_ip.map(|Extension(ClientIp(ip))| ip);
+    let (token, refresh_token) = issue_tokens(&client, &state, &user, source_ip.as_deref()).await?;
+
+    Ok(Json(user.to_user_info(Some(token), Some(refresh_token))))
+}
+
+fn oauth_user_from_item(
+    user_type: UserType,
+    item: HashMap<String, AttributeValue>,
+) -> Result<UserWrapper, HandlerError> {
+    match user_type {
+        UserType::Buyer => Ok(UserWrapper::from(serde_dynamo::from_item::<_, Buyer>(item)?)),
+        UserType::Seller => Ok(UserWrapper::from(serde_dynamo::from_item::<_, Seller>(
+            item,
+        )?)),
+        UserType::Admin => unreachable!("rejected in oauth_callback before lookup"),
+    }
+}
+
+fn provision_oauth_user(user_type: UserType, id: &str, email: &str) -> UserWrapper {
+    let now = chrono::Local::now().timestamp_millis() as u64;
+    match user_type {
+        UserType::Buyer => UserWrapper::from(Buyer {
+            id: id.to_string(),
+            create_at: now,
+            is_active: true,
+            first_name: String::new(),
+            last_name: String::new(),
+            email: email.to_string(),
+            fund: 0,
+            fund_on_hold: 0,
+            // OAuth-provisioned accounts never go through the OPAQUE login path;
+            // this is an unusable placeholder, not a credential.
+            opaque_registration: Ulid::new().to_string(),
+            wallet_address: None,
+        }),
+        UserType::Seller => UserWrapper::from(Seller {
+            id: id.to_string(),
+            create_at: now,
+            is_active: true,
+            first_name: String::new(),
+            last_name: String::new(),
+            email: email.to_string(),
+            fund: 0,
+            opaque_registration: Ulid::new().to_string(),
+        }),
+        UserType::Admin => unreachable!("rejected in oauth_callback before lookup"),
+    }
+}
+
+/// Invalidate the caller's current token and the session behind it, so the
+/// matching refresh token can no longer mint replacement access tokens either.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/logout",
+    tag = "Auth",
+    responses(
+        (status = OK, description = "Token and session revoked"),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn logout(
+    Extension(claim): Extension<ClaimOwned>,
+    State(state): State<Arc<AppState>>,
+) -> Result<(), HandlerError> {
+    let client = Client::new(&state.aws_config);
+    revoke_token(&client, &state, &claim.jti).await?;
+    revoke_session(&client, &claim.sid).await
+}
+
+/// Invalidate every token ever minted for the caller's account.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/revoke-all",
+    tag = "Auth",
+    responses(
+        (status = OK, description = "All tokens revoked"),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn revoke_all(
+    Extension(claim): Extension<ClaimOwned>,
+    State(state): State<Arc<AppState>>,
+) -> Result<(), HandlerError> {
+    let client = Client::new(&state.aws_config);
+
+    let mut exclusive_start_key = None;
+    loop {
+        let mut req = client
+            .query()
+            .table_name(ACCESS_TOKEN_TABLE)
+            .index_name(ACCESS_TOKEN_USER_INDEX)
+            .key_condition_expression("userId = :uid")
+            .expression_attribute_values(":uid", AttributeValue::S(claim.id.clone()));
+        if let Some(key) = exclusive_start_key.take() {
+            req = req.set_exclusive_start_key(Some(key));
+        }
+        let resp = req.send().await?;
+
+        for item in resp.items() {
+            if let Some(jti) = item.get("tokenId").and_then(|v| v.as_s().ok()) {
+                revoke_token(&client, &state, jti).await?;
+            }
+        }
+
+        exclusive_start_key = resp.last_evaluated_key().cloned();
+        if exclusive_start_key.is_none() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+async fn revoke_token(client: &Client, state: &AppState, jti: &str) -> Result<(), HandlerError> {
+    client
+        .update_item()
+        .table_name(ACCESS_TOKEN_TABLE)
+        .key("tokenId", AttributeValue::S(jti.to_string()))
+        .update_expression("SET valid = :invalid")
+        .expression_attribute_values(":invalid", AttributeValue::Bool(false))
+        .send()
+        .await?;
+
+    state.token_cache.insert(jti.to_string(), false).await;
+
+    Ok(())
+}
+
+/// List the caller's active (unrevoked, unexpired) sessions.
+#[utoipa::path(
+    get,
+    path = "/v1/sessions",
+    tag = "Auth",
+    responses(
+        (status = OK, description = "Active sessions", body = Vec<SessionInfo>),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn list_sessions(
+    Extension(claim): Extension<ClaimOwned>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<SessionInfo>>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let now = chrono::Local::now().timestamp() as u64;
+
+    let mut sessions = Vec::new();
+    let mut exclusive_start_key = None;
+    loop {
+        let mut req = client
+            .query()
+            .table_name(SESSION_TABLE)
+            .index_name(SESSION_USER_INDEX)
+            .key_condition_expression("userId = :uid")
+            .expression_attribute_values(":uid", AttributeValue::S(claim.id.clone()));
+        if let Some(key) = exclusive_start_key.take() {
+            req = req.set_exclusive_start_key(Some(key));
+        }
+        let resp = req.send().await?;
+
+        for item in resp.items() {
+            let revoked = item
+                .get("revoked")
+                .and_then(|v| v.as_bool().ok())
+                .copied()
+                .unwrap_or(false);
+            let expire_at: u64 = item
+                .get("expireAt")
+                .and_then(|v| v.as_n().ok())
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(0);
+            if revoked || expire_at <= now {
+                continue;
+            }
+
+            let (Some(id), Some(issued_at)) = (
+                item.get("id").and_then(|v| v.as_s().ok()).cloned(),
+                item.get("issuedAt")
+                    .and_then(|v| v.as_n().ok())
+                    .and_then(|n| n.parse().ok()),
+            ) else {
+                continue;
+            };
+            let source_ip = item.get("sourceIp").and_then(|v| v.as_s().ok()).cloned();
+
+            sessions.push(SessionInfo {
+                id,
+                issued_at,
+                expire_at,
+                source_ip,
+            });
+        }
+
+        exclusive_start_key = resp.last_evaluated_key().cloned();
+        if exclusive_start_key.is_none() {
+            break;
+        }
+    }
+
+    Ok(Json(sessions))
+}
+
+/// Revoke one of the caller's own sessions, e.g. to kill a lost or stolen
+/// refresh token without waiting for it to expire.
+#[utoipa::path(
+    delete,
+    path = "/v1/sessions/{id}",
+    tag = "Auth",
+    params(
+        ("id" = String, Path, description = "Session id to revoke"),
+    ),
+    responses(
+        (status = OK, description = "Session revoked"),
+        (status = NOT_FOUND, description = "No such session for this user", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn delete_session(
+    Extension(claim): Extension<ClaimOwned>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<(), HandlerError> {
+    let client = Client::new(&state.aws_config);
+    let resp = client
+        .get_item()
+        .table_name(SESSION_TABLE)
+        .key("id", AttributeValue::S(id.clone()))
+        .send()
+        .await?;
+    let item = resp.item.ok_or_else(HandlerError::not_found)?;
+    let owner = item.get("userId").and_then(|v| v.as_s().ok());
+    if owner != Some(&claim.id) {
+        return Err(HandlerError::not_found());
+    }
+
+    revoke_session(&client, &id).await
+}
+
+async fn find_buyer_by_wallet(
+    client: &Client,
+    address: &str,
+) -> Result<Option<Buyer>, HandlerError> {
+    let resp = client
+        .query()
+        .table_name(BUYER_TABLE)
+        .index_name(WALLET_ADDRESS_INDEX)
+        .key_condition_expression("walletAddress = :addr")
+        .expression_attribute_values(":addr", AttributeValue::S(address.to_string()))
+        .send()
+        .await?;
+
+    match resp.items().first() {
+        Some(item) => Ok(Some(serde_dynamo::from_item(item.clone())?)),
+        None => Ok(None),
+    }
+}
+
+fn generate_nonce() -> String {
+    const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    let mut bytes = [0u8; 24];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+        .iter()
+        .map(|b| CHARSET[*b as usize % CHARSET.len()] as char)
+        .collect()
+}
+
+fn decode_hex_signature(signature: &str) -> Result<Vec<u8>, HandlerError> {
+    let hex = signature.strip_prefix("0x").unwrap_or(signature);
+    if hex.len() % 2 != 0 {
+        return Err(SiweError::MalformedSignature.into());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| SiweError::MalformedSignature.into())
+        })
+        .collect()
 }
@@ -1,25 +1,35 @@
-use std::sync::Arc;
+use std::{convert::Infallible, pin::Pin, sync::Arc};
 
 use aws_sdk_dynamodb::{
     types::{AttributeValue, Put, ReturnValue, TransactWriteItem, Update},
     Client,
 };
-use axum::{extract::State, http::StatusCode, Extension, Json};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    Extension, Json,
+};
 use serde_dynamo::{from_attribute_value, from_item, from_items, to_attribute_value, to_item};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
 use ulid::Ulid;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
-    constants::{BID_TABLE, BUYER_TABLE, ITEM_TABLE, PURCHASE_TABLE},
+    constants::{
+        BID_TABLE, BUYER_TABLE, ITEM_TABLE, MIN_BID_INCREMENT, PLACE_BID_MAX_RETRIES,
+        PURCHASE_TABLE,
+    },
     errors::HandlerError,
     models::{
         auth::ClaimOwned,
-        bid::{Bid, BidItemRequest, BidRef, Purchase},
+        bid::{resolve_proxy_bid, Bid, BidEvent, BidEventKind, BidItemRequest, BidRef, Purchase},
         buyer::{AddFundRequest, AddFundResponse},
         item::{ItemRef, ItemState},
         user::UserType,
     },
     state::AppState,
+    transact::{is_transaction_canceled, transact_chunked},
 };
 
 use super::check_user;
@@ -30,6 +40,7 @@ pub fn route() -> OpenApiRouter<Arc<AppState>> {
         .routes(routes!(buyer_place_bid))
         .routes(routes!(buyer_active_bids))
         .routes(routes!(buyer_purchases))
+        .routes(routes!(buyer_bid_stream))
 }
 
 /// Add fund to buyer
@@ -85,10 +96,17 @@ async fn buyer_add_fund(
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct PlaceBidProjection {
+    state: ItemState,
     current_bid: Option<BidRef>,
+    init_price: u64,
+    bid_version: u64,
 }
 
 /// Place bid to an item
+///
+/// Bids are proxy (automatic-maximum) bids: the caller submits a hidden
+/// `max_amount` and the engine derives the visible `amount` against the
+/// current leader, topping it up just enough to stay ahead.
 #[utoipa::path(
     post,
     path = "/bid",
@@ -96,7 +114,9 @@ struct PlaceBidProjection {
     request_body = BidItemRequest,
     responses(
         (status = OK, description = "Place bid success", body = BidRef),
+        (status = BAD_REQUEST, description = "max_amount below init_price, or item not active", body = HandlerError),
         (status = FORBIDDEN, description = "Not a buyer", body = HandlerError),
+        (status = CONFLICT, description = "Too much contention on this item, retries exhausted", body = HandlerError),
         (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
     ),
     security(
@@ -112,97 +132,353 @@ async fn buyer_place_bid(
 
     let client = Client::new(&state.aws_config);
 
-    let bid = Bid {
-        buyer_id: claim.id.clone(),
-        id: Ulid::new(),
-        create_at: chrono::Local::now().timestamp_millis() as u64,
-        item: ItemRef {
-            seller_id: payload.seller_id.clone(),
-            id: payload.id,
-        },
-        amount: payload.amount,
-        is_active: true,
+    let item_ref = ItemRef {
+        seller_id: payload.seller_id.clone(),
+        id: payload.id,
     };
 
-    let bid_ref = BidRef::from(&bid);
+    // The item's `currentBid`/`pastBids` can move between our read and our
+    // write, so every attempt re-reads the item and re-derives the proxy-bid
+    // outcome from scratch, then stakes the write on `bidVersion` being
+    // unchanged since that read. A changed `bidVersion` cancels the
+    // transaction rather than corrupting state, so we just retry with a
+    // fresh read.
+    for _ in 0..PLACE_BID_MAX_RETRIES {
+        let get_item_project = client
+            .get_item()
+            .table_name(ITEM_TABLE)
+            .key("sellerId", AttributeValue::S(payload.seller_id.clone()))
+            .key("id", AttributeValue::S(payload.id.to_string()))
+            .projection_expression("state, currentBid, initPrice, bidVersion")
+            .send()
+            .await?;
+
+        let project: PlaceBidProjection =
+            from_item(get_item_project.item.ok_or(HandlerError::not_found())?)?;
+
+        if project.state != ItemState::Active {
+            return Err(HandlerError::HandlerError(
+                StatusCode::BAD_REQUEST,
+                "Item is not open for bidding".to_string(),
+            ));
+        }
 
-    let get_item_project = client
-        .get_item()
-        .table_name(BID_TABLE)
-        .key("sellerId", AttributeValue::S(payload.seller_id.clone()))
-        .key("id", AttributeValue::S(payload.id.to_string()))
-        .projection_expression("currentBid")
-        .send()
-        .await?;
+        if payload.max_amount < project.init_price {
+            return Err(HandlerError::HandlerError(
+                StatusCode::BAD_REQUEST,
+                "max_amount must be at least the item's init_price".to_string(),
+            ));
+        }
 
-    let project: PlaceBidProjection =
-        from_item(get_item_project.item.ok_or(HandlerError::not_found())?)?;
-
-    let put_bid = TransactWriteItem::builder()
-        .put(
-            Put::builder()
-                .table_name(BID_TABLE)
-                .set_item(Some(to_item(bid)?))
-                .build()?,
-        )
-        .build();
-
-    let update_buyer = TransactWriteItem::builder()
-        .update(
-            Update::builder()
-                .table_name(BUYER_TABLE)
-                .key("id", AttributeValue::S(claim.id.clone()))
-                .update_expression("SET fund = fund - :amount, fundOnHold = fundOnHold + :amount")
-                .condition_expression("fund >= :amount")
-                .expression_attribute_values(":amount", to_attribute_value(payload.amount)?)
-                .build()?,
-        )
-        .build();
-
-    let update_item = TransactWriteItem::builder()
-        .update(
-            Update::builder()
-                .table_name(ITEM_TABLE)
-                .key("sellerId", AttributeValue::S(payload.seller_id))
-                .key("id", AttributeValue::S(payload.id.to_string()))
-                .update_expression(
-                    "SET currentBid = :bid, pastBids = list_append(pastBids, :bid_list)",
+        let leader = match &project.current_bid {
+            Some(leader_ref) => {
+                let get_leader = client
+                    .get_item()
+                    .table_name(BID_TABLE)
+                    .key("buyerId", AttributeValue::S(leader_ref.buyer_id.clone()))
+                    .key("id", AttributeValue::S(leader_ref.id.to_string()))
+                    .send()
+                    .await?;
+                let leader_bid: Bid =
+                    from_item(get_leader.item.ok_or(HandlerError::not_found())?)?;
+                Some((leader_ref.clone(), leader_bid))
+            }
+            None => None,
+        };
+
+        let outcome = resolve_proxy_bid(
+            leader.as_ref().map(|(_, b)| b.max_amount),
+            payload.max_amount,
+            project.init_price,
+            MIN_BID_INCREMENT,
+        );
+        let leader_bid_ref = leader.as_ref().map(|(r, _)| r.clone());
+
+        let bid = Bid {
+            buyer_id: claim.id.clone(),
+            id: Ulid::new(),
+            create_at: chrono::Local::now().timestamp_millis() as u64,
+            item: item_ref.clone(),
+            amount: if outcome.new_bid_wins {
+                outcome.leader_amount
+            } else {
+                payload.max_amount
+            },
+            max_amount: payload.max_amount,
+            is_active: outcome.new_bid_wins,
+        };
+        let bid_ref = BidRef::from(&bid);
+
+        let put_bid = TransactWriteItem::builder()
+            .put(
+                Put::builder()
+                    .table_name(BID_TABLE)
+                    .set_item(Some(to_item(bid.clone())?))
+                    .build()?,
+            )
+            .build();
+
+        let mut transact_items = vec![put_bid];
+
+        let next_version = project.bid_version + 1;
+
+        if outcome.new_bid_wins {
+            let update_item = TransactWriteItem::builder()
+                .update(
+                    Update::builder()
+                        .table_name(ITEM_TABLE)
+                        .key("sellerId", AttributeValue::S(item_ref.seller_id.clone()))
+                        .key("id", AttributeValue::S(item_ref.id.to_string()))
+                        .update_expression(
+                            "SET currentBid = :bid, currentBidAmount = :amount, \
+                             pastBids = list_append(pastBids, :bid_list), bidVersion = :newVersion",
+                        )
+                        .condition_expression(
+                            "state = :active AND bidVersion = :expectedVersion AND \
+                             (attribute_not_exists(currentBidAmount) OR :amount > currentBidAmount)",
+                        )
+                        .expression_attribute_values(":bid", to_attribute_value(bid_ref.clone())?)
+                        .expression_attribute_values(
+                            ":bid_list",
+                            to_attribute_value([bid_ref.clone()])?,
+                        )
+                        .expression_attribute_values(":amount", to_attribute_value(bid.amount)?)
+                        .expression_attribute_values(":active", ItemState::Active.into())
+                        .expression_attribute_values(
+                            ":expectedVersion",
+                            to_attribute_value(project.bid_version)?,
+                        )
+                        .expression_attribute_values(
+                            ":newVersion",
+                            to_attribute_value(next_version)?,
+                        )
+                        .build()?,
                 )
-                .condition_expression("state = :active")
-                .expression_attribute_values(":bid", to_attribute_value(bid_ref.clone())?)
-                .expression_attribute_values(":bid_list", to_attribute_value([bid_ref.clone()])?)
-                .expression_attribute_values(":active", ItemState::Active.into())
-                .build()?,
-        )
-        .build();
-
-    let transaction = client
-        .transact_write_items()
-        .transact_items(put_bid)
-        .transact_items(update_buyer)
-        .transact_items(update_item);
-
-    match project.current_bid {
-        Some(b) => {
-            let update_bid = TransactWriteItem::builder()
+                .build();
+
+            transact_items.push(update_item);
+
+            match leader {
+                // The new leader is the same buyer raising their own proxy
+                // max: a separate `update_buyer` + `refund_leader` would both
+                // target this buyer's `BUYER_TABLE` item in one
+                // `transact_write_items` call, which DynamoDB rejects with
+                // `ValidationException` rather than
+                // `TransactionCanceledException` (so the retry loop below
+                // never sees it as retryable). Fold the hold increase and the
+                // old-max refund into a single signed adjustment instead.
+                Some((leader_ref, leader_bid)) if leader_ref.buyer_id == claim.id => {
+                    let delta = payload.max_amount as i64 - leader_bid.max_amount as i64;
+
+                    let adjust_buyer = TransactWriteItem::builder()
+                        .update(
+                            Update::builder()
+                                .table_name(BUYER_TABLE)
+                                .key("id", AttributeValue::S(claim.id.clone()))
+                                .update_expression(
+                                    "SET fund = fund - :delta, fundOnHold = fundOnHold + :delta",
+                                )
+                                .condition_expression("fund >= :delta AND fundOnHold >= :negDelta")
+                                .expression_attribute_values(":delta", to_attribute_value(delta)?)
+                                .expression_attribute_values(
+                                    ":negDelta",
+                                    to_attribute_value(-delta)?,
+                                )
+                                .build()?,
+                        )
+                        .build();
+
+                    let deactivate_leader = TransactWriteItem::builder()
+                        .update(
+                            Update::builder()
+                                .table_name(BID_TABLE)
+                                .key("buyerId", AttributeValue::S(leader_ref.buyer_id.clone()))
+                                .key("id", AttributeValue::S(leader_ref.id.to_string()))
+                                .update_expression("SET isActive = :false")
+                                .expression_attribute_values(":false", AttributeValue::Bool(false))
+                                .build()?,
+                        )
+                        .build();
+
+                    transact_items.push(adjust_buyer);
+                    transact_items.push(deactivate_leader);
+                }
+                Some((leader_ref, leader_bid)) => {
+                    let update_buyer = TransactWriteItem::builder()
+                        .update(
+                            Update::builder()
+                                .table_name(BUYER_TABLE)
+                                .key("id", AttributeValue::S(claim.id.clone()))
+                                .update_expression(
+                                    "SET fund = fund - :amount, fundOnHold = fundOnHold + :amount",
+                                )
+                                .condition_expression("fund >= :amount")
+                                .expression_attribute_values(
+                                    ":amount",
+                                    to_attribute_value(payload.max_amount)?,
+                                )
+                                .build()?,
+                        )
+                        .build();
+
+                    let deactivate_leader = TransactWriteItem::builder()
+                        .update(
+                            Update::builder()
+                                .table_name(BID_TABLE)
+                                .key("buyerId", AttributeValue::S(leader_ref.buyer_id.clone()))
+                                .key("id", AttributeValue::S(leader_ref.id.to_string()))
+                                .update_expression("SET isActive = :false")
+                                .expression_attribute_values(":false", AttributeValue::Bool(false))
+                                .build()?,
+                        )
+                        .build();
+
+                    let refund_leader = TransactWriteItem::builder()
+                        .update(
+                            Update::builder()
+                                .table_name(BUYER_TABLE)
+                                .key("id", AttributeValue::S(leader_ref.buyer_id.clone()))
+                                .update_expression(
+                                    "SET fund = fund + :amount, fundOnHold = fundOnHold - :amount",
+                                )
+                                .condition_expression("fundOnHold >= :amount")
+                                .expression_attribute_values(
+                                    ":amount",
+                                    to_attribute_value(leader_bid.max_amount)?,
+                                )
+                                .build()?,
+                        )
+                        .build();
+
+                    transact_items.push(update_buyer);
+                    transact_items.push(deactivate_leader);
+                    transact_items.push(refund_leader);
+                }
+                None => {
+                    let update_buyer = TransactWriteItem::builder()
+                        .update(
+                            Update::builder()
+                                .table_name(BUYER_TABLE)
+                                .key("id", AttributeValue::S(claim.id.clone()))
+                                .update_expression(
+                                    "SET fund = fund - :amount, fundOnHold = fundOnHold + :amount",
+                                )
+                                .condition_expression("fund >= :amount")
+                                .expression_attribute_values(
+                                    ":amount",
+                                    to_attribute_value(payload.max_amount)?,
+                                )
+                                .build()?,
+                        )
+                        .build();
+
+                    transact_items.push(update_buyer);
+                }
+            }
+        } else {
+            let (leader_ref, _) = leader.expect("leader exists whenever a bid can lose");
+
+            let bump_leader = TransactWriteItem::builder()
                 .update(
                     Update::builder()
                         .table_name(BID_TABLE)
-                        .key("buyer_id", AttributeValue::S(b.buyer_id))
-                        .key("id", AttributeValue::S(b.id.to_string()))
-                        .update_expression("SET isActive = :false")
-                        .expression_attribute_values(":false", AttributeValue::Bool(false))
+                        .key("buyerId", AttributeValue::S(leader_ref.buyer_id.clone()))
+                        .key("id", AttributeValue::S(leader_ref.id.to_string()))
+                        .update_expression("SET amount = :amount")
+                        .expression_attribute_values(
+                            ":amount",
+                            to_attribute_value(outcome.leader_amount)?,
+                        )
                         .build()?,
                 )
                 .build();
-            transaction.transact_items(update_bid)
+
+            let update_item = TransactWriteItem::builder()
+                .update(
+                    Update::builder()
+                        .table_name(ITEM_TABLE)
+                        .key("sellerId", AttributeValue::S(item_ref.seller_id.clone()))
+                        .key("id", AttributeValue::S(item_ref.id.to_string()))
+                        .update_expression(
+                            "SET currentBidAmount = :amount, \
+                             pastBids = list_append(pastBids, :bid_list), bidVersion = :newVersion",
+                        )
+                        .condition_expression("state = :active AND bidVersion = :expectedVersion")
+                        .expression_attribute_values(
+                            ":bid_list",
+                            to_attribute_value([bid_ref.clone()])?,
+                        )
+                        .expression_attribute_values(
+                            ":amount",
+                            to_attribute_value(outcome.leader_amount)?,
+                        )
+                        .expression_attribute_values(":active", ItemState::Active.into())
+                        .expression_attribute_values(
+                            ":expectedVersion",
+                            to_attribute_value(project.bid_version)?,
+                        )
+                        .expression_attribute_values(
+                            ":newVersion",
+                            to_attribute_value(next_version)?,
+                        )
+                        .build()?,
+                )
+                .build();
+
+            transact_items.push(bump_leader);
+            transact_items.push(update_item);
+        }
+
+        match transact_chunked(&client, transact_items).await {
+            Ok(_) => {
+                if outcome.new_bid_wins {
+                    if let Some(leader_bid_ref) = leader_bid_ref {
+                        state.bid_events.publish(
+                            &item_ref,
+                            BidEvent {
+                                kind: BidEventKind::Outbid,
+                                item: item_ref.clone(),
+                                current_bid: Some(bid_ref.clone()),
+                                amount: Some(bid.amount),
+                                target_buyer_id: Some(leader_bid_ref.buyer_id),
+                            },
+                        );
+                    }
+                    state.bid_events.publish(
+                        &item_ref,
+                        BidEvent {
+                            kind: BidEventKind::PriceUpdate,
+                            item: item_ref.clone(),
+                            current_bid: Some(bid_ref.clone()),
+                            amount: Some(bid.amount),
+                            target_buyer_id: None,
+                        },
+                    );
+                } else {
+                    let leader_bid_ref =
+                        leader_bid_ref.expect("leader exists whenever a bid can lose");
+                    state.bid_events.publish(
+                        &item_ref,
+                        BidEvent {
+                            kind: BidEventKind::PriceUpdate,
+                            item: item_ref.clone(),
+                            current_bid: Some(leader_bid_ref),
+                            amount: Some(outcome.leader_amount),
+                            target_buyer_id: None,
+                        },
+                    );
+                }
+
+                return Ok(Json(bid_ref));
+            }
+            Err(e) if is_transaction_canceled(&e) => continue,
+            Err(e) => return Err(e),
         }
-        None => transaction,
     }
-    .send()
-    .await?;
 
-    Ok(Json(bid_ref))
+    Err(HandlerError::BidConflict(
+        "Too much contention on this item right now, please try again".to_string(),
+    ))
 }
 
 /// Get active bids
@@ -244,6 +520,65 @@ async fn buyer_active_bids(
     Ok(Json(result))
 }
 
+/// Live bid/outbid/settlement notifications for the caller's active bids.
+///
+/// Subscribes to the broadcast channel of every item the buyer currently
+/// holds an active bid on, merges them into one stream, and forwards only
+/// the events relevant to the buyer: ones aimed at them by `target_buyer_id`,
+/// plus every item-wide update (`price-update`, `auction-ended`).
+#[utoipa::path(
+    get,
+    path = "/bid-stream",
+    tag = "Buyer",
+    responses(
+        (status = OK, description = "SSE stream of bid events"),
+        (status = FORBIDDEN, description = "Not a buyer", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+    security(
+        ("http-jwt" = []),
+    ),
+)]
+async fn buyer_bid_stream(
+    Extension(claim): Extension<ClaimOwned>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, HandlerError> {
+    check_user(claim.as_claim(), UserType::Buyer)?;
+
+    let client = Client::new(&state.aws_config);
+
+    let query_bids_resp = client
+        .query()
+        .table_name(BID_TABLE)
+        .key_condition_expression("buyerId = :id")
+        .filter_expression("isActive = :true")
+        .expression_attribute_values(":id", AttributeValue::S(claim.id.clone()))
+        .expression_attribute_values(":true", AttributeValue::Bool(true))
+        .send()
+        .await?;
+    let active_bids: Vec<Bid> = from_items(query_bids_resp.items().to_vec())?;
+
+    let mut merged: Pin<Box<dyn Stream<Item = BidEvent> + Send>> = Box::pin(tokio_stream::empty());
+    for bid in &active_bids {
+        let item_events = BroadcastStream::new(state.bid_events.subscribe(&bid.item))
+            .filter_map(|event| event.ok());
+        merged = Box::pin(merged.merge(item_events));
+    }
+
+    let buyer_id = claim.id.clone();
+    let stream = merged
+        .filter(move |event| {
+            event.target_buyer_id.is_none() || event.target_buyer_id.as_deref() == Some(&buyer_id)
+        })
+        .map(|event| {
+            Ok(Event::default()
+                .json_data(&event)
+                .expect("BidEvent always serializes"))
+        });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
 /// Get purchases
 #[utoipa::path(
     get,
@@ -0,0 +1,248 @@
+use std::{collections::HashMap, io::Write, io::Read, sync::Arc};
+
+use aws_sdk_dynamodb::{types::AttributeValue, Client};
+use aws_sdk_s3::primitives::ByteStream;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde_dynamo::{from_item, to_item};
+use ulid::Ulid;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::{
+    constants::{
+        BID_TABLE, BUYER_TABLE, DUMP_BUCKET, DUMP_SCHEMA_VERSION, DUMP_TABLE, ITEM_TABLE,
+        PURCHASE_TABLE, SELLER_TABLE,
+    },
+    errors::HandlerError,
+    models::dump::{Dump, DumpRecord, DumpStatus, ImportRequest},
+    state::AppState,
+};
+
+/// Tables snapshotted by an export. Internal operational tables (tasks,
+/// dumps themselves) are intentionally excluded.
+const EXPORTED_TABLES: &[&str] = &[
+    ITEM_TABLE,
+    BID_TABLE,
+    PURCHASE_TABLE,
+    BUYER_TABLE,
+    SELLER_TABLE,
+];
+
+pub fn router() -> OpenApiRouter<Arc<AppState>> {
+    OpenApiRouter::new()
+        .routes(routes!(create_dump))
+        .routes(routes!(get_dump))
+        .routes(routes!(import_dump))
+}
+
+async fn put_dump(client: &Client, dump: &Dump) -> Result<(), HandlerError> {
+    client
+        .put_item()
+        .table_name(DUMP_TABLE)
+        .set_item(Some(to_item(dump.clone())?))
+        .send()
+        .await?;
+    Ok(())
+}
+
+async fn scan_all(
+    client: &Client,
+    table: &str,
+) -> Result<Vec<HashMap<String, AttributeValue>>, HandlerError> {
+    let mut items = Vec::new();
+    let mut exclusive_start_key = None;
+
+    loop {
+        let mut req = client.scan().table_name(table);
+        if let Some(key) = exclusive_start_key.take() {
+            req = req.set_exclusive_start_key(Some(key));
+        }
+        let resp = req.send().await?;
+        items.extend(resp.items().to_vec());
+
+        exclusive_start_key = resp.last_evaluated_key().cloned();
+        if exclusive_start_key.is_none() {
+            break;
+        }
+    }
+
+    Ok(items)
+}
+
+/// Scan every exported table and write a single gzip-compressed NDJSON
+/// archive to S3, one line per record tagged with its source table and the
+/// archive's schema version. Returns the archive's S3 key.
+async fn export_all_tables(state: &AppState) -> Result<String, HandlerError> {
+    let dynamo = Client::new(&state.aws_config);
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+
+    for table in EXPORTED_TABLES {
+        for raw_item in scan_all(&dynamo, table).await? {
+            let item: serde_json::Value = from_item(raw_item)?;
+            let record = DumpRecord {
+                table: (*table).to_string(),
+                schema_version: DUMP_SCHEMA_VERSION,
+                item,
+            };
+            serde_json::to_writer(&mut encoder, &record)?;
+            encoder.write_all(b"\n")?;
+        }
+    }
+
+    let archive = encoder.finish()?;
+    let key = format!("dumps/{}.ndjson.gz", Ulid::new());
+
+    state
+        .s3
+        .put_object()
+        .bucket(DUMP_BUCKET)
+        .key(&key)
+        .body(ByteStream::from(archive))
+        .send()
+        .await?;
+
+    Ok(key)
+}
+
+/// Enqueue (and, inline, run) a full export of all auction data.
+#[utoipa::path(
+    post,
+    path = "/",
+    tag = "Dump",
+    responses(
+        (status = OK, description = "Dump job result", body = Dump),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+    security(
+        ("http-jwt" = []),
+    ),
+)]
+async fn create_dump(State(state): State<Arc<AppState>>) -> Result<Json<Dump>, HandlerError> {
+    let dynamo = Client::new(&state.aws_config);
+
+    let mut dump = Dump::new();
+    put_dump(&dynamo, &dump).await?;
+
+    dump.status = DumpStatus::InProgress;
+    put_dump(&dynamo, &dump).await?;
+
+    match export_all_tables(&state).await {
+        Ok(key) => {
+            dump.status = DumpStatus::Done;
+            dump.download_key = Some(key);
+        }
+        Err(e) => {
+            dump.status = DumpStatus::Failed;
+            dump.error = Some(e.to_string());
+        }
+    }
+    dump.finished_at = Some(chrono::Local::now().timestamp_millis() as u64);
+    put_dump(&dynamo, &dump).await?;
+
+    Ok(Json(dump))
+}
+
+/// Report the status of a dump export job.
+#[utoipa::path(
+    get,
+    path = "/{dumpId}",
+    tag = "Dump",
+    params(
+        ("dumpId" = String, Path, description = "Dump job ID to get", format = Ulid),
+    ),
+    responses(
+        (status = OK, description = "Returns the dump job", body = Dump),
+        (status = NOT_FOUND, description = "Dump not found", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+    security(
+        ("http-jwt" = []),
+    ),
+)]
+async fn get_dump(
+    State(state): State<Arc<AppState>>,
+    Path(dump_id): Path<Ulid>,
+) -> Result<Json<Dump>, HandlerError> {
+    let dynamo = Client::new(&state.aws_config);
+
+    let get_resp = dynamo
+        .get_item()
+        .table_name(DUMP_TABLE)
+        .key("id", AttributeValue::S(dump_id.to_string()))
+        .send()
+        .await?;
+
+    let item = get_resp.item.ok_or(HandlerError::not_found())?;
+    let dump: Dump = from_item(item)?;
+
+    Ok(Json(dump))
+}
+
+/// Restore a previously exported archive, writing each record back into its
+/// source table with its own `put_item` (no batch writes) after checking that
+/// record's schema version and table. A rejected record stops the import;
+/// the records before it stay written.
+#[utoipa::path(
+    post,
+    path = "/import",
+    tag = "Dump",
+    request_body = ImportRequest,
+    responses(
+        (status = OK, description = "Import complete"),
+        (status = BAD_REQUEST, description = "Unsupported schema version", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+    security(
+        ("http-jwt" = []),
+    ),
+)]
+async fn import_dump(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ImportRequest>,
+) -> Result<(), HandlerError> {
+    let object = state
+        .s3
+        .get_object()
+        .bucket(DUMP_BUCKET)
+        .key(&payload.archive_key)
+        .send()
+        .await?;
+    let bytes = object.body.collect().await?.into_bytes();
+
+    let mut raw = String::new();
+    GzDecoder::new(&bytes[..]).read_to_string(&mut raw)?;
+
+    let dynamo = Client::new(&state.aws_config);
+    for line in raw.lines().filter(|l| !l.is_empty()) {
+        let record: DumpRecord = serde_json::from_str(line)?;
+        if record.schema_version != DUMP_SCHEMA_VERSION {
+            return Err(HandlerError::HandlerError(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Unsupported schema version {} for table {}",
+                    record.schema_version, record.table
+                ),
+            ));
+        }
+        if !EXPORTED_TABLES.contains(&record.table.as_str()) {
+            return Err(HandlerError::HandlerError(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown table {} in dump archive", record.table),
+            ));
+        }
+
+        let item: HashMap<String, AttributeValue> = to_item(record.item)?;
+        dynamo
+            .put_item()
+            .table_name(&record.table)
+            .set_item(Some(item))
+            .send()
+            .await?;
+    }
+
+    Ok(())
+}
@@ -1,34 +1,51 @@
-use std::sync::Arc;
+use std::{convert::Infallible, sync::Arc, time::Duration};
 
 use aws_sdk_dynamodb::{types::AttributeValue, Client};
+use aws_sdk_s3::presigning::PresigningConfig;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
     Extension, Json,
 };
 use chrono::TimeDelta;
 use serde_dynamo::{from_item, from_items};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
 use ulid::Ulid;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
-    constants::ITEM_TABLE,
+    constants::{
+        DEFAULT_PAGE_LIMIT, IMAGE_BUCKET, IMAGE_MAX_COUNT_PER_ITEM, IMAGE_PRESIGN_EXPIRY_SECS,
+        ITEM_TABLE,
+    },
     errors::HandlerError,
     models::{
         auth::ClaimOwned,
-        item::{CheckItemExiprationResponse, Item, ItemState},
+        bid::BidEvent,
+        item::{
+            CheckItemExiprationResponse, ImagePresignRequest, ImagePresignResponse, Item,
+            ItemImage, ItemRef, ItemState, ListQuery, PagedItemsResponse, PresignedImageDownload,
+            PresignedImageUpload, PresignedUrl,
+        },
+        search::{self, SearchRequest, SearchResponse},
         user::UserType,
     },
     routes::check_user,
     state::AppState,
+    utils::{decode_cursor, encode_cursor},
 };
 
 pub fn router() -> OpenApiRouter<Arc<AppState>> {
     OpenApiRouter::new()
         .routes(routes!(get_item))
+        .routes(routes!(get_item_events))
         .routes(routes!(get_active_items))
         .routes(routes!(check_item_expiration))
         .routes(routes!(get_recently_sold))
+        .routes(routes!(search_items))
+        .routes(routes!(presign_item_images))
+        .routes(routes!(get_item_images))
 }
 
 // Get Item
@@ -71,34 +88,87 @@ async fn get_item(
     Ok(Json(result))
 }
 
+/// Stream live events for a single item.
+///
+/// Unlike `/buyer/bid-stream` (which merges every item a buyer is actively
+/// bidding on), this subscribes to one item's channel, so anyone watching the
+/// listing page gets a live price ticker without polling `get_item`.
+#[utoipa::path(
+    get,
+    path = "/{sellerId}/{itemId}/events",
+    tag = "Item",
+    params(
+        ("sellerId" = String, Path, description = "Seller of the item"),
+        ("itemId" = String, Path, description = "Item ID to watch", format = Ulid),
+    ),
+    responses(
+        (status = OK, description = "SSE stream of live item events", body = BidEvent),
+    ),
+)]
+async fn get_item_events(
+    State(state): State<Arc<AppState>>,
+    Path((seller_id, item_id)): Path<(String, Ulid)>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let item_ref = ItemRef {
+        seller_id,
+        id: item_id,
+    };
+
+    let stream = BroadcastStream::new(state.bid_events.subscribe(&item_ref))
+        .filter_map(|event| event.ok())
+        .map(|event| {
+            Ok(Event::default()
+                .json_data(&event)
+                .expect("BidEvent always serializes"))
+        });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
 // Get Item
 /// Get all active items
 #[utoipa::path(
     get,
     path = "/active",
     tag = "Item",
+    params(
+        ("limit" = Option<u32>, Query, description = "Max items to return"),
+        ("cursor" = Option<String>, Query, description = "Opaque cursor from a previous page"),
+    ),
     responses(
-        (status = OK, description = "Return active items", body = Vec<Item>),
+        (status = OK, description = "Return active items", body = PagedItemsResponse),
+        (status = BAD_REQUEST, description = "Invalid cursor", body = HandlerError),
         (status = NOT_FOUND, description = "Item not found", body = HandlerError),
         (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
     ),
 )]
 async fn get_active_items(
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Vec<Item>>, HandlerError> {
+    Query(query): Query<ListQuery>,
+) -> Result<Json<PagedItemsResponse>, HandlerError> {
     let client = Client::new(&state.aws_config);
 
-    let get_item_resp = client
+    let mut scan = client
         .scan()
         .table_name(ITEM_TABLE)
         .filter_expression("state = :active")
         .expression_attribute_values(":active", ItemState::Active.into())
-        .send()
-        .await?;
+        .limit(query.limit.unwrap_or(DEFAULT_PAGE_LIMIT) as i32);
 
-    let result = from_items(get_item_resp.items().to_vec())?;
+    if let Some(cursor) = &query.cursor {
+        scan = scan.set_exclusive_start_key(Some(decode_cursor(cursor)?));
+    }
 
-    Ok(Json(result))
+    let get_item_resp = scan.send().await?;
+
+    let items = from_items(get_item_resp.items().to_vec())?;
+    let next_cursor = get_item_resp
+        .last_evaluated_key()
+        .cloned()
+        .map(encode_cursor)
+        .transpose()?;
+
+    Ok(Json(PagedItemsResponse { items, next_cursor }))
 }
 
 /// Check Expiration Status of the Item
@@ -154,8 +224,13 @@ async fn check_item_expiration(
     get,
     path = "/recently-sold",
     tag = "Item",
+    params(
+        ("limit" = Option<u32>, Query, description = "Max items to return"),
+        ("cursor" = Option<String>, Query, description = "Opaque cursor from a previous page"),
+    ),
     responses(
-        (status = OK, description = "Return recently sold items", body = Vec<Item>),
+        (status = OK, description = "Return recently sold items", body = PagedItemsResponse),
+        (status = BAD_REQUEST, description = "Invalid cursor", body = HandlerError),
         (status = FORBIDDEN, description = "Not a buyer", body = HandlerError),
         (status = NOT_FOUND, description = "Item not found", body = HandlerError),
         (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
@@ -167,26 +242,32 @@ async fn check_item_expiration(
 async fn get_recently_sold(
     Extension(claim): Extension<ClaimOwned>,
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Vec<Item>>, HandlerError> {
+    Query(query): Query<ListQuery>,
+) -> Result<Json<PagedItemsResponse>, HandlerError> {
     check_user(claim.as_claim(), UserType::Buyer)?;
 
     let client = Client::new(&state.aws_config);
 
-    let get_item_resp = client
+    let mut scan = client
         .scan()
         .table_name(ITEM_TABLE)
         .filter_expression("state = :archived AND soldTime <> :null")
         .expression_attribute_values(":archived", ItemState::Archived.into())
         .expression_attribute_values(":null", AttributeValue::Null(true))
-        .send()
-        .await?;
+        .limit(query.limit.unwrap_or(DEFAULT_PAGE_LIMIT) as i32);
+
+    if let Some(cursor) = &query.cursor {
+        scan = scan.set_exclusive_start_key(Some(decode_cursor(cursor)?));
+    }
+
+    let get_item_resp = scan.send().await?;
 
     let result: Vec<Item> = from_items(get_item_resp.items().to_vec())?;
 
     let now = chrono::Local::now().timestamp_millis();
     let delta = TimeDelta::days(1).num_milliseconds();
 
-    let filtered = result
+    let items = result
         .into_iter()
         .filter(|item| {
             item.sold_time.map_or(false, |t| {
@@ -196,5 +277,224 @@ async fn get_recently_sold(
         })
         .collect();
 
-    Ok(Json(filtered))
+    let next_cursor = get_item_resp
+        .last_evaluated_key()
+        .cloned()
+        .map(encode_cursor)
+        .transpose()?;
+
+    Ok(Json(PagedItemsResponse { items, next_cursor }))
+}
+
+/// Typo-tolerant, faceted item search.
+#[utoipa::path(
+    post,
+    path = "/search",
+    tag = "Item",
+    request_body = SearchRequest,
+    responses(
+        (status = OK, description = "Ranked, paginated search hits", body = SearchResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn search_items(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SearchRequest>,
+) -> Result<Json<SearchResponse>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+
+    let mut scan = client.scan().table_name(ITEM_TABLE);
+
+    let mut filters: Vec<&str> = Vec::new();
+    if let Some(item_state) = payload.state.clone() {
+        filters.push("state = :state");
+        scan = scan.expression_attribute_values(":state", item_state.into());
+    }
+    if let Some(seller_id) = &payload.seller_id {
+        filters.push("sellerId = :sellerId");
+        scan = scan.expression_attribute_values(":sellerId", AttributeValue::S(seller_id.clone()));
+    }
+    if let Some(min_price) = payload.min_price {
+        filters.push("initPrice >= :minPrice");
+        scan = scan.expression_attribute_values(":minPrice", AttributeValue::N(min_price.to_string()));
+    }
+    if let Some(max_price) = payload.max_price {
+        filters.push("initPrice <= :maxPrice");
+        scan = scan.expression_attribute_values(":maxPrice", AttributeValue::N(max_price.to_string()));
+    }
+    if !filters.is_empty() {
+        scan = scan.filter_expression(filters.join(" AND "));
+    }
+
+    let scan_resp = scan.send().await?;
+    let items: Vec<Item> = from_items(scan_resp.items().to_vec())?;
+
+    let started_at = std::time::Instant::now();
+    let mut response = search::execute(items, &payload);
+    response.processing_time_ms = started_at.elapsed().as_millis() as u64;
+
+    Ok(Json(response))
+}
+
+/// Mint presigned upload URLs for an item's images.
+#[utoipa::path(
+    post,
+    path = "/images/presign",
+    tag = "Item",
+    request_body = ImagePresignRequest,
+    responses(
+        (status = OK, description = "Presigned upload URLs", body = ImagePresignResponse),
+        (status = BAD_REQUEST, description = "Unsupported content type or too many images", body = HandlerError),
+        (status = FORBIDDEN, description = "Not the item's seller", body = HandlerError),
+        (status = NOT_FOUND, description = "Item not found", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+    security(
+        ("http-jwt" = []),
+    ),
+)]
+async fn presign_item_images(
+    Extension(claim): Extension<ClaimOwned>,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ImagePresignRequest>,
+) -> Result<Json<ImagePresignResponse>, HandlerError> {
+    check_user(claim.as_claim(), UserType::Seller)?;
+    if claim.id != payload.seller_id {
+        return Err(HandlerError::HandlerError(
+            StatusCode::FORBIDDEN,
+            "Cannot presign images for another seller's item".to_string(),
+        ));
+    }
+
+    validate_image_content_type(&payload.content_type)?;
+
+    let dynamo = Client::new(&state.aws_config);
+    let get_item_resp = dynamo
+        .get_item()
+        .table_name(ITEM_TABLE)
+        .key("sellerId", AttributeValue::S(payload.seller_id.clone()))
+        .key("id", AttributeValue::S(payload.item_id.to_string()))
+        .projection_expression("images")
+        .send()
+        .await?;
+    let projection: ImagesProjection =
+        from_item(get_item_resp.item.ok_or(HandlerError::not_found())?)?;
+
+    if projection.images.len() + payload.count as usize > IMAGE_MAX_COUNT_PER_ITEM {
+        return Err(HandlerError::HandlerError(
+            StatusCode::BAD_REQUEST,
+            format!("Item may not have more than {IMAGE_MAX_COUNT_PER_ITEM} images"),
+        ));
+    }
+
+    let expiry = PresigningConfig::expires_in(Duration::from_secs(IMAGE_PRESIGN_EXPIRY_SECS))?;
+
+    let mut uploads = Vec::with_capacity(payload.count as usize);
+    for _ in 0..payload.count {
+        let key = format!(
+            "items/{}/{}/{}",
+            payload.seller_id,
+            payload.item_id,
+            Ulid::new()
+        );
+        let presigned = state
+            .s3
+            .put_object()
+            .bucket(IMAGE_BUCKET)
+            .key(&key)
+            .content_type(&payload.content_type)
+            .presigned(expiry.clone())
+            .await?;
+        uploads.push(PresignedImageUpload {
+            key,
+            upload_url: presigned.uri().to_string(),
+        });
+    }
+
+    Ok(Json(ImagePresignResponse { uploads }))
+}
+
+/// Check that `content_type` is one of the image MIME types the upload
+/// pipeline knows how to thumbnail, matching `routes::seller::image_format_for`.
+fn validate_image_content_type(content_type: &str) -> Result<(), HandlerError> {
+    match content_type {
+        "image/jpeg" | "image/png" | "image/webp" => Ok(()),
+        other => Err(HandlerError::HandlerError(
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported image type: {other}"),
+        )),
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImagesProjection {
+    images: Vec<ItemImage>,
+}
+
+/// Get presigned download URLs for an item's stored images.
+#[utoipa::path(
+    get,
+    path = "/{sellerId}/{itemId}/images",
+    tag = "Item",
+    params(
+        ("sellerId" = String, Path, description = "Seller of the item"),
+        ("itemId" = String, Path, description = "Item ID to get images for", format = Ulid),
+    ),
+    responses(
+        (status = OK, description = "Presigned download URLs", body = Vec<PresignedImageDownload>),
+        (status = NOT_FOUND, description = "Item not found", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+    security(
+        ("http-jwt" = []),
+    ),
+)]
+async fn get_item_images(
+    State(state): State<Arc<AppState>>,
+    Path((seller_id, item_id)): Path<(String, Ulid)>,
+) -> Result<Json<Vec<PresignedImageDownload>>, HandlerError> {
+    let dynamo = Client::new(&state.aws_config);
+    let get_item_resp = dynamo
+        .get_item()
+        .table_name(ITEM_TABLE)
+        .key("sellerId", AttributeValue::S(seller_id))
+        .key("id", AttributeValue::S(item_id.to_string()))
+        .projection_expression("images")
+        .send()
+        .await?;
+
+    let projection: ImagesProjection = from_item(get_item_resp.item.ok_or(HandlerError::not_found())?)?;
+
+    let expiry = PresigningConfig::expires_in(Duration::from_secs(IMAGE_PRESIGN_EXPIRY_SECS))?;
+
+    let mut downloads = Vec::with_capacity(projection.images.len());
+    for image in projection.images {
+        let original = state
+            .s3
+            .get_object()
+            .bucket(IMAGE_BUCKET)
+            .key(&image.original)
+            .presigned(expiry.clone())
+            .await?;
+        let thumbnail = state
+            .s3
+            .get_object()
+            .bucket(IMAGE_BUCKET)
+            .key(&image.thumbnail)
+            .presigned(expiry.clone())
+            .await?;
+        downloads.push(PresignedImageDownload {
+            original: PresignedUrl {
+                key: image.original,
+                download_url: original.uri().to_string(),
+            },
+            thumbnail: PresignedUrl {
+                key: image.thumbnail,
+                download_url: thumbnail.uri().to_string(),
+            },
+        });
+    }
+
+    Ok(Json(downloads))
 }
@@ -7,8 +7,10 @@ use crate::{
 
 pub mod auth;
 pub mod buyer;
+pub mod dump;
 pub mod item;
 pub mod seller;
+pub mod task;
 
 fn check_user(claim: Claim, user_type: UserType) -> Result<(), HandlerError> {
     if claim.user_type != user_type {
@@ -1,27 +1,35 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, io::Cursor, sync::Arc};
 
-use aws_sdk_dynamodb::{
-    types::{AttributeValue, Put, TransactWriteItem, Update},
-    Client,
-};
+use aws_sdk_dynamodb::{types::AttributeValue, Client};
+use aws_sdk_s3::primitives::ByteStream;
 use axum::{
-    extract::{Json, Path, State},
+    extract::{DefaultBodyLimit, Json, Multipart, Path, State},
     http::StatusCode,
     Extension,
 };
+use image::ImageFormat;
 use serde_dynamo::{from_item, from_items, to_attribute_value, to_item};
 use ulid::Ulid;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
-    constants::{BID_TABLE, BUYER_TABLE, ITEM_TABLE, PURCHASE_TABLE, SELLER_TABLE},
+    constants::{
+        BID_TABLE, BUYER_TABLE, IMAGE_BUCKET, IMAGE_THUMBNAIL_MAX_EDGE, IMAGE_UPLOAD_MAX_BYTES,
+        ITEM_TABLE, PURCHASE_TABLE, SELLER_TABLE,
+    },
     errors::HandlerError,
+    gateway::{is_gateway_condition_check_failed, Key, WriteOp},
     models::{
         auth::{Claim, ClaimOwned},
-        bid::{Bid, Purchase},
-        item::{AddItemRequest, Item, ItemRef, ItemState, UpdateItemRequest},
+        bid::{Bid, BidEvent, BidEventKind, Purchase},
+        item::{
+            AddItemRequest, Item, ItemImage, ItemRef, ItemState, ItemTransition,
+            UpdateItemRequest, ITEM_STATE_ATTR,
+        },
+        task::{Task, TaskKind},
         user::UserType,
     },
+    routes::task::put_task,
     state::AppState,
 };
 
@@ -37,6 +45,8 @@ pub fn router() -> OpenApiRouter<Arc<AppState>> {
         .routes(routes!(seller_unpublish_item_by_id))
         .routes(routes!(seller_fulfill_item_by_id))
         .routes(routes!(seller_archive_item_by_id))
+        .routes(routes!(seller_upload_item_images))
+        .layer(DefaultBodyLimit::max(IMAGE_UPLOAD_MAX_BYTES))
 }
 
 fn check_user(claim: Claim) -> Result<(), HandlerError> {
@@ -49,6 +59,14 @@ fn check_user(claim: Claim) -> Result<(), HandlerError> {
     Ok(())
 }
 
+/// Primary key for an item row, as addressed in `ITEM_TABLE`.
+fn item_key(seller_id: &str, item_id: Ulid) -> Key {
+    HashMap::from([
+        ("sellerId".to_string(), AttributeValue::S(seller_id.to_string())),
+        ("id".to_string(), AttributeValue::S(item_id.to_string())),
+    ])
+}
+
 // Review Items
 /// Get all of seller's items.
 #[utoipa::path(
@@ -67,17 +85,16 @@ async fn seller_get_owned_items(
 ) -> Result<Json<Vec<Item>>, HandlerError> {
     check_user(claim.as_claim())?;
 
-    let client = Client::new(&state.aws_config);
-
-    let query_item_resp = client
-        .query()
-        .table_name(ITEM_TABLE)
-        .key_condition_expression("sellerId = :sid")
-        .expression_attribute_values(":sid", AttributeValue::S(claim.id.clone()))
-        .send()
+    let db_items = state
+        .gateway
+        .query_items(
+            ITEM_TABLE,
+            "sellerId = :sid",
+            HashMap::from([(":sid".to_string(), AttributeValue::S(claim.id.clone()))]),
+        )
         .await?;
 
-    let items: Vec<Item> = from_items(query_item_resp.items().to_vec())?;
+    let items: Vec<Item> = from_items(db_items)?;
 
     Ok(Json(items))
 }
@@ -102,18 +119,11 @@ async fn seller_add_item(
 ) -> Result<Json<ItemRef>, HandlerError> {
     check_user(claim.as_claim())?;
 
-    let client = Client::new(&state.aws_config);
-
     let new_item = Item::new_from_request(claim.id.clone(), payload);
     let iref = ItemRef::from(&new_item);
     let item = to_item(new_item)?;
 
-    client
-        .put_item()
-        .table_name(ITEM_TABLE)
-        .set_item(Some(item))
-        .send()
-        .await?;
+    state.gateway.put_item(ITEM_TABLE, item).await?;
 
     Ok(Json(iref))
 }
@@ -141,19 +151,13 @@ async fn seller_get_item_by_id(
 ) -> Result<Json<Item>, HandlerError> {
     check_user(claim.as_claim())?;
 
-    let client = Client::new(&state.aws_config);
-
-    let get_item_resp = client
-        .get_item()
-        .table_name(ITEM_TABLE)
-        .key("sellerId", AttributeValue::S(claim.id.clone()))
-        .key("id", AttributeValue::S(item_id.to_string()))
-        .send()
-        .await?;
-
-    let item = get_item_resp.item.ok_or(HandlerError::not_found())?;
+    let db_item = state
+        .gateway
+        .get_item(ITEM_TABLE, item_key(&claim.id, item_id))
+        .await?
+        .ok_or(HandlerError::not_found())?;
 
-    let result = from_item(item)?;
+    let result = from_item(db_item)?;
 
     Ok(Json(result))
 }
@@ -181,23 +185,29 @@ async fn seller_delete_item_by_id(
 ) -> Result<(), HandlerError> {
     check_user(claim.as_claim())?;
 
-    let client = Client::new(&state.aws_config);
-
-    let delete_item_resp = client
-        .delete_item()
-        .table_name(ITEM_TABLE)
-        .key("sellerId", AttributeValue::S(claim.id.clone()))
-        .key("id", AttributeValue::S(item_id.to_string()))
-        .condition_expression("itemState = :val")
-        .expression_attribute_values(":val", AttributeValue::S(ItemState::InActive.to_string()))
-        .send()
+    let key = item_key(&claim.id, item_id);
+
+    state
+        .gateway
+        .get_item(ITEM_TABLE, key.clone())
+        .await?
+        .ok_or(HandlerError::not_found())?;
+
+    state
+        .gateway
+        .transaction(vec![WriteOp::Delete {
+            table: ITEM_TABLE,
+            key,
+            condition_expression: Some("#state = :val".to_string()),
+            names: HashMap::from([("#state".to_string(), ITEM_STATE_ATTR.to_string())]),
+            values: HashMap::from([(
+                ":val".to_string(),
+                AttributeValue::S(ItemState::InActive.to_string()),
+            )]),
+        }])
         .await?;
 
-    if delete_item_resp.attributes().is_none() {
-        Err(HandlerError::not_found())
-    } else {
-        Ok(())
-    }
+    Ok(())
 }
 
 // Edit item
@@ -213,6 +223,7 @@ async fn seller_delete_item_by_id(
     responses(
         (status = OK, description = "Update item success"),
         (status = BAD_REQUEST, description = "Bad update request", body = HandlerError),
+        (status = CONFLICT, description = "Item left the inactive state before the edit committed", body = HandlerError),
         (status = FORBIDDEN, description = "Not a seller", body = HandlerError),
         (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
     ),
@@ -235,14 +246,6 @@ async fn seller_update_item_by_id(
     let mut update_expr: Vec<&str> = Vec::new();
     let mut eavs: HashMap<String, AttributeValue> = HashMap::new();
 
-    let client = Client::new(&state.aws_config);
-    let mut update_item_cmd = client
-        .update_item()
-        .table_name(ITEM_TABLE)
-        .key("sellerId", AttributeValue::S(claim.id.clone()))
-        .key("id", AttributeValue::S(item_id.to_string()))
-        .condition_expression("itemState = :state");
-
     eavs.insert(
         ":state".to_string(),
         AttributeValue::S(ItemState::InActive.to_string()),
@@ -276,14 +279,20 @@ async fn seller_update_item_by_id(
 
     if let Some(images) = payload.images {
         update_expr.push("images = :images");
-        eavs.insert(":images".to_string(), AttributeValue::Ss(images));
+        eavs.insert(":images".to_string(), to_attribute_value(images)?);
     }
 
-    update_item_cmd = update_item_cmd
-        .update_expression(format!("SET {}", update_expr.join(", ")))
-        .set_expression_attribute_values(Some(eavs));
-
-    update_item_cmd.send().await?;
+    state
+        .gateway
+        .transaction(vec![WriteOp::Update {
+            table: ITEM_TABLE,
+            key: item_key(&claim.id, item_id),
+            update_expression: format!("SET {}", update_expr.join(", ")),
+            condition_expression: Some("#state = :state".to_string()),
+            names: HashMap::from([("#state".to_string(), ITEM_STATE_ATTR.to_string())]),
+            values: eavs,
+        }])
+        .await?;
 
     Ok(())
 }
@@ -307,6 +316,7 @@ struct PublishSubItem {
     responses(
         (status = OK, description = "Item delete success"),
         (status = BAD_REQUEST, description = "Bad request", body = HandlerError),
+        (status = CONFLICT, description = "Item left the inactive state before publish committed", body = HandlerError),
         (status = FORBIDDEN, description = "Not a seller", body = HandlerError),
         (status = NOT_FOUND, description = "Item not found", body = HandlerError),
         (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
@@ -319,38 +329,60 @@ async fn seller_publish_item_by_id(
 ) -> Result<(), HandlerError> {
     check_user(claim.as_claim())?;
 
-    let client = Client::new(&state.aws_config);
-
-    let get_item_resp = client
-        .get_item()
-        .key("sellerId", AttributeValue::S(claim.id.clone()))
-        .key("id", AttributeValue::S(item_id.to_string()))
-        .projection_expression("state, auctionLength")
-        .send()
-        .await?;
+    let item_ref = ItemRef {
+        seller_id: claim.id.clone(),
+        id: item_id,
+    };
+    let key = item_key(&claim.id, item_id);
 
-    let item: PublishSubItem = from_item(get_item_resp.item.ok_or(HandlerError::not_found())?)?;
+    let db_item = state
+        .gateway
+        .get_item(ITEM_TABLE, key.clone())
+        .await?
+        .ok_or(HandlerError::not_found())?;
+    let item: PublishSubItem = from_item(db_item)?;
 
-    if item.state != ItemState::InActive {
-        return Err(HandlerError::HandlerError(
-            StatusCode::BAD_REQUEST,
-            "Item need to be inactive".to_string(),
-        ));
+    if !ItemTransition::Publish.allowed_from(&item.state) {
+        return Err(ItemTransition::Publish.invalid_state_error());
     }
 
     let sdate = chrono::Local::now().timestamp_millis();
     let edate = sdate + item.auction_length;
 
-    client
-        .update_item()
-        .key("sellerId", AttributeValue::S(claim.id))
-        .key("id", AttributeValue::S(item_id.to_string()))
-        .update_expression("SET state = :state, startDate = :sdate, endDate = :edate")
-        .expression_attribute_values(":state", ItemState::Active.into())
-        .expression_attribute_values(":sdate", to_attribute_value(sdate)?)
-        .expression_attribute_values(":edate", to_attribute_value(edate)?)
-        .send()
-        .await?;
+    let (condition, guard_values) = ItemTransition::Publish.guard();
+    let mut values: HashMap<String, AttributeValue> = guard_values.into_iter().collect();
+    values.insert(":sdate".to_string(), to_attribute_value(sdate)?);
+    values.insert(":edate".to_string(), to_attribute_value(edate)?);
+
+    if let Err(e) = state
+        .gateway
+        .transaction(vec![WriteOp::Update {
+            table: ITEM_TABLE,
+            key,
+            update_expression: "SET #state = :toState, startDate = :sdate, endDate = :edate".to_string(),
+            condition_expression: Some(condition),
+            names: HashMap::from([("#state".to_string(), ITEM_STATE_ATTR.to_string())]),
+            values,
+        }])
+        .await
+    {
+        return if is_gateway_condition_check_failed(&e) {
+            Err(ItemTransition::Publish.conflict_error())
+        } else {
+            Err(e)
+        };
+    }
+
+    state.bid_events.publish(
+        &item_ref,
+        BidEvent {
+            kind: BidEventKind::Published,
+            item: item_ref.clone(),
+            current_bid: None,
+            amount: None,
+            target_buyer_id: None,
+        },
+    );
 
     Ok(())
 }
@@ -366,6 +398,7 @@ async fn seller_publish_item_by_id(
     ),
     responses(
         (status = OK, description = "Item unpublish success", body = Item),
+        (status = CONFLICT, description = "Item left the active state, or gained a bid, before unpublish committed", body = HandlerError),
         (status = FORBIDDEN, description = "Not a seller", body = HandlerError),
         (status = NOT_FOUND, description = "Item not found", body = HandlerError),
         (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
@@ -378,20 +411,47 @@ async fn seller_unpublish_item_by_id(
 ) -> Result<(), HandlerError> {
     check_user(claim.as_claim())?;
 
-    let client = Client::new(&state.aws_config);
+    let item_ref = ItemRef {
+        seller_id: claim.id.clone(),
+        id: item_id,
+    };
 
-    client
-        .update_item()
-        .key("sellerId", AttributeValue::S(claim.id))
-        .key("id", AttributeValue::S(item_id.to_string()))
-        .update_expression("SET state = :state, startDate = :null, endDate = :null")
-        .condition_expression("state = :old_state, currentBid = :null, size(pastBids) = :zero")
-        .expression_attribute_values(":state", ItemState::InActive.into())
-        .expression_attribute_values(":old_state", ItemState::Active.into())
-        .expression_attribute_values(":null", AttributeValue::Null(true))
-        .expression_attribute_values(":zero", AttributeValue::N("0".to_string()))
-        .send()
-        .await?;
+    let (transition_condition, guard_values) = ItemTransition::Unpublish.guard();
+    let mut values: HashMap<String, AttributeValue> = guard_values.into_iter().collect();
+    values.insert(":null".to_string(), AttributeValue::Null(true));
+    values.insert(":zero".to_string(), AttributeValue::N("0".to_string()));
+
+    if let Err(e) = state
+        .gateway
+        .transaction(vec![WriteOp::Update {
+            table: ITEM_TABLE,
+            key: item_key(&claim.id, item_id),
+            update_expression: "SET #state = :toState, startDate = :null, endDate = :null".to_string(),
+            condition_expression: Some(format!(
+                "({transition_condition}) AND currentBid = :null AND size(pastBids) = :zero"
+            )),
+            names: HashMap::from([("#state".to_string(), ITEM_STATE_ATTR.to_string())]),
+            values,
+        }])
+        .await
+    {
+        return if is_gateway_condition_check_failed(&e) {
+            Err(ItemTransition::Unpublish.conflict_error())
+        } else {
+            Err(e)
+        };
+    }
+
+    state.bid_events.publish(
+        &item_ref,
+        BidEvent {
+            kind: BidEventKind::Unpublished,
+            item: item_ref.clone(),
+            current_bid: None,
+            amount: None,
+            target_buyer_id: None,
+        },
+    );
 
     Ok(())
 }
@@ -407,6 +467,7 @@ async fn seller_unpublish_item_by_id(
     responses(
         (status = OK, description = "Item fulfill success"),
         (status = BAD_REQUEST, description = "Item cannot be fulfilled yet", body = HandlerError),
+        (status = CONFLICT, description = "Item left the completed state before fulfill committed", body = HandlerError),
         (status = FORBIDDEN, description = "Not a seller", body = HandlerError),
         (status = NOT_FOUND, description = "Item not found", body = HandlerError),
         (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
@@ -419,33 +480,30 @@ async fn seller_fulfill_item_by_id(
 ) -> Result<(), HandlerError> {
     check_user(claim.as_claim())?;
 
-    let client = Client::new(&state.aws_config);
-
-    let get_item_resp = client
-        .get_item()
-        .table_name(ITEM_TABLE)
-        .key("sellerId", AttributeValue::S(claim.id.clone()))
-        .key("id", AttributeValue::S(item_id.to_string()))
-        .send()
-        .await?;
-    let db_item = get_item_resp.item.ok_or(HandlerError::not_found())?;
+    let db_item = state
+        .gateway
+        .get_item(ITEM_TABLE, item_key(&claim.id, item_id))
+        .await?
+        .ok_or(HandlerError::not_found())?;
     let item: Item = from_item(db_item)?;
-    if item.state != ItemState::Completed || item.current_bid.is_none() {
+    if !ItemTransition::Fulfill.allowed_from(&item.state) {
+        return Err(ItemTransition::Fulfill.invalid_state_error());
+    }
+    let Some(curr_bid_ref) = item.current_bid.as_ref() else {
         return Err(HandlerError::HandlerError(
             StatusCode::BAD_REQUEST,
             "This item cannot be fulfilled.".to_string(),
         ));
-    }
-
-    let curr_bid_ref = item.current_bid.as_ref().unwrap();
-    let get_bid_resp = client
-        .get_item()
-        .table_name(BID_TABLE)
-        .key("buyerId", AttributeValue::S(curr_bid_ref.buyer_id.clone()))
-        .key("id", AttributeValue::S(curr_bid_ref.id.to_string()))
-        .send()
-        .await?;
-    let db_bid = get_bid_resp.item.ok_or(HandlerError::not_found())?;
+    };
+    let bid_key = HashMap::from([
+        ("buyerId".to_string(), AttributeValue::S(curr_bid_ref.buyer_id.clone())),
+        ("id".to_string(), AttributeValue::S(curr_bid_ref.id.to_string())),
+    ]);
+    let db_bid = state
+        .gateway
+        .get_item(BID_TABLE, bid_key.clone())
+        .await?
+        .ok_or(HandlerError::not_found())?;
     let bid: Bid = from_item(db_bid)?;
 
     let seller_income = ((bid.amount as f64) * 0.95).floor() as u64;
@@ -462,74 +520,94 @@ async fn seller_fulfill_item_by_id(
         sold_time: bid.create_at,
     };
 
-    let seller_update = TransactWriteItem::builder()
-        .update(
-            Update::builder()
-                .table_name(SELLER_TABLE)
-                .key("id", AttributeValue::S(claim.id.clone()))
-                .update_expression("SET fund = fund + :amount")
-                .expression_attribute_values(":amount", to_attribute_value(seller_income)?)
-                .build()?,
-        )
-        .build();
-
-    let buyer_update = TransactWriteItem::builder()
-        .update(
-            Update::builder()
-                .table_name(BUYER_TABLE)
-                .key("id", AttributeValue::S(bid.buyer_id.clone()))
-                .update_expression("SET fundOnHold = fundOnHold - :amount")
-                .condition_expression("fundOnHold >= :amount")
-                .expression_attribute_values(":amount", to_attribute_value(bid.amount)?)
-                .build()?,
-        )
-        .build();
-
-    let purchase_put = TransactWriteItem::builder()
-        .put(
-            Put::builder()
-                .table_name(PURCHASE_TABLE)
-                .set_item(Some(to_item(purchase)?))
-                .build()?,
-        )
-        .build();
-
-    let item_update = TransactWriteItem::builder()
-        .update(
-            Update::builder()
-                .table_name(ITEM_TABLE)
-                .key("sellerId", AttributeValue::S(claim.id.clone()))
-                .key("id", AttributeValue::S(item_id.to_string()))
-                .update_expression("SET soldBid = :bid_ref, soldTime = :time, soldPrice = :price, state = :archived")
-                .expression_attribute_values(":bid_ref", to_attribute_value(curr_bid_ref.clone())?)
-                .expression_attribute_values(":time", to_attribute_value(bid.create_at)?)
-                .expression_attribute_values(":price", to_attribute_value(bid.amount)?)
-                .expression_attribute_values(":state", to_attribute_value(ItemState::Archived)?)
-                .build()?,
-        )
-        .build();
-
-    let bid_update = TransactWriteItem::builder()
-        .update(
-            Update::builder()
-                .table_name(BID_TABLE)
-                .key("buyerId", AttributeValue::S(curr_bid_ref.buyer_id.clone()))
-                .key("id", AttributeValue::S(curr_bid_ref.id.to_string()))
-                .update_expression("SET isActive = :false")
-                .expression_attribute_values(":false", AttributeValue::Bool(false))
-                .build()?,
-        )
-        .build();
+    let seller_update = WriteOp::Update {
+        table: SELLER_TABLE,
+        key: HashMap::from([("id".to_string(), AttributeValue::S(claim.id.clone()))]),
+        update_expression: "SET fund = fund + :amount".to_string(),
+        condition_expression: None,
+        names: HashMap::new(),
+        values: HashMap::from([(":amount".to_string(), to_attribute_value(seller_income)?)]),
+    };
 
-    client
-        .transact_write_items()
-        .transact_items(seller_update)
-        .transact_items(buyer_update)
-        .transact_items(purchase_put)
-        .transact_items(item_update)
-        .transact_items(bid_update)
-        .send()
-        .await?;
+    let buyer_update = WriteOp::Update {
+        table: BUYER_TABLE,
+        key: HashMap::from([("id".to_string(), AttributeValue::S(bid.buyer_id.clone()))]),
+        update_expression: "SET fundOnHold = fundOnHold - :amount".to_string(),
+        condition_expression: Some("fundOnHold >= :amount".to_string()),
+        names: HashMap::new(),
+        // `buyer_place_bid` holds the hidden `max_amount`, not the visible
+        // clearing `amount`, so the release must match it or the difference
+        // is stranded in fundOnHold forever.
+        values: HashMap::from([(":amount".to_string(), to_attribute_value(bid.max_amount)?)]),
+    };
+
+    let purchase_put = WriteOp::Put {
+        table: PURCHASE_TABLE,
+        item: to_item(purchase)?,
+    };
+
+    let (transition_condition, guard_values) = ItemTransition::Fulfill.guard();
+    let mut item_values: HashMap<String, AttributeValue> = guard_values.into_iter().collect();
+    item_values.insert(":bid_ref".to_string(), to_attribute_value(curr_bid_ref.clone())?);
+    item_values.insert(":time".to_string(), to_attribute_value(bid.create_at)?);
+    item_values.insert(":price".to_string(), to_attribute_value(bid.amount)?);
+    let item_update = WriteOp::Update {
+        table: ITEM_TABLE,
+        key: item_key(&claim.id, item_id),
+        update_expression: "SET soldBid = :bid_ref, soldTime = :time, soldPrice = :price, #state = :toState"
+            .to_string(),
+        condition_expression: Some(transition_condition),
+        names: HashMap::from([("#state".to_string(), ITEM_STATE_ATTR.to_string())]),
+        values: item_values,
+    };
+
+    let bid_update = WriteOp::Update {
+        table: BID_TABLE,
+        key: bid_key,
+        update_expression: "SET isActive = :false".to_string(),
+        condition_expression: None,
+        names: HashMap::new(),
+        values: HashMap::from([(":false".to_string(), AttributeValue::Bool(false))]),
+    };
+
+    let item_ref = ItemRef {
+        seller_id: claim.id.clone(),
+        id: item_id,
+    };
+
+    if let Err(e) = state
+        .gateway
+        .transaction(vec![
+            seller_update,
+            buyer_update,
+            purchase_put,
+            item_update,
+            bid_update,
+        ])
+        .await
+    {
+        return if is_gateway_condition_check_failed(&e) {
+            Err(ItemTransition::Fulfill.conflict_error())
+        } else {
+            Err(e)
+        };
+    }
+
+    if !item.past_bids.is_empty() {
+        let client = Client::new(&state.aws_config);
+        put_task(&client, &Task::new(TaskKind::RefundBid, item_ref.clone())).await?;
+    }
+
+    state.bid_events.publish(
+        &item_ref,
+        BidEvent {
+            kind: BidEventKind::Completed,
+            item: item_ref.clone(),
+            current_bid: Some(curr_bid_ref.clone()),
+            amount: Some(bid.amount),
+            target_buyer_id: Some(bid.buyer_id),
+        },
+    );
 
     Ok(())
 }
@@ -544,6 +622,7 @@ async fn seller_fulfill_item_by_id(
     ),
     responses(
         (status = OK, description = "Item archive success"),
+        (status = CONFLICT, description = "Item left the inactive/failed state before archive committed", body = HandlerError),
         (status = FORBIDDEN, description = "Not a seller", body = HandlerError),
         (status = NOT_FOUND, description = "Item not found", body = HandlerError),
         (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
@@ -556,19 +635,156 @@ async fn seller_archive_item_by_id(
 ) -> Result<(), HandlerError> {
     check_user(claim.as_claim())?;
 
+    let (condition, guard_values) = ItemTransition::Archive.guard();
+
+    if let Err(e) = state
+        .gateway
+        .transaction(vec![WriteOp::Update {
+            table: ITEM_TABLE,
+            key: item_key(&claim.id, item_id),
+            update_expression: "SET #state = :toState".to_string(),
+            condition_expression: Some(condition),
+            names: HashMap::from([("#state".to_string(), ITEM_STATE_ATTR.to_string())]),
+            values: guard_values.into_iter().collect(),
+        }])
+        .await
+    {
+        return if is_gateway_condition_check_failed(&e) {
+            Err(ItemTransition::Archive.conflict_error())
+        } else {
+            Err(e)
+        };
+    }
+
+    Ok(())
+}
+
+/// Map an uploaded image's MIME type to its S3 key extension and `image` crate format.
+fn image_format_for(content_type: &str) -> Result<(ImageFormat, &'static str), HandlerError> {
+    match content_type {
+        "image/jpeg" => Ok((ImageFormat::Jpeg, "jpg")),
+        "image/png" => Ok((ImageFormat::Png, "png")),
+        "image/webp" => Ok((ImageFormat::WebP, "webp")),
+        other => Err(HandlerError::HandlerError(
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported image type: {other}"),
+        )),
+    }
+}
+
+/// Upload one or more images for an item, generating a downscaled thumbnail
+/// alongside each original and recording both S3 keys on the item.
+#[utoipa::path(
+    post,
+    path = "/item/{itemId}/images",
+    tag = "Seller",
+    params(
+        ("itemId" = String, Path, description = "Item ID to add images to", format = Ulid),
+    ),
+    request_body(content = Vec<u8>, content_type = "multipart/form-data"),
+    responses(
+        (status = OK, description = "Stored original + thumbnail keys", body = Vec<ItemImage>),
+        (status = BAD_REQUEST, description = "Invalid or unsupported image upload", body = HandlerError),
+        (status = FORBIDDEN, description = "Not a seller", body = HandlerError),
+        (status = NOT_FOUND, description = "Item not found", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+)]
+async fn seller_upload_item_images(
+    Extension(claim): Extension<ClaimOwned>,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Ulid>,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<ItemImage>>, HandlerError> {
+    check_user(claim.as_claim())?;
+
     let client = Client::new(&state.aws_config);
 
+    let get_item_resp = client
+        .get_item()
+        .table_name(ITEM_TABLE)
+        .key("sellerId", AttributeValue::S(claim.id.clone()))
+        .key("id", AttributeValue::S(item_id.to_string()))
+        .projection_expression("id")
+        .send()
+        .await?;
+    get_item_resp.item.ok_or(HandlerError::not_found())?;
+
+    let mut images = Vec::new();
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        HandlerError::HandlerError(StatusCode::BAD_REQUEST, format!("Malformed upload: {e}"))
+    })? {
+        let content_type = field
+            .content_type()
+            .ok_or_else(|| {
+                HandlerError::HandlerError(
+                    StatusCode::BAD_REQUEST,
+                    "Image part is missing a content type".to_string(),
+                )
+            })?
+            .to_string();
+        let (format, ext) = image_format_for(&content_type)?;
+
+        let bytes = field.bytes().await.map_err(|e| {
+            HandlerError::HandlerError(StatusCode::BAD_REQUEST, format!("Failed to read upload: {e}"))
+        })?;
+
+        let decoded = image::load_from_memory_with_format(&bytes, format)?;
+        let thumbnail = decoded.resize(
+            IMAGE_THUMBNAIL_MAX_EDGE,
+            IMAGE_THUMBNAIL_MAX_EDGE,
+            image::imageops::FilterType::Lanczos3,
+        );
+        let mut thumbnail_bytes = Cursor::new(Vec::new());
+        thumbnail.write_to(&mut thumbnail_bytes, format)?;
+
+        let id = Ulid::new();
+        let original_key = format!("items/{}/{}/{}.{}", claim.id, item_id, id, ext);
+        let thumbnail_key = format!("items/{}/{}/{}_thumb.{}", claim.id, item_id, id, ext);
+
+        state
+            .s3
+            .put_object()
+            .bucket(IMAGE_BUCKET)
+            .key(&original_key)
+            .content_type(&content_type)
+            .body(ByteStream::from(bytes.to_vec()))
+            .send()
+            .await?;
+
+        state
+            .s3
+            .put_object()
+            .bucket(IMAGE_BUCKET)
+            .key(&thumbnail_key)
+            .content_type(&content_type)
+            .body(ByteStream::from(thumbnail_bytes.into_inner()))
+            .send()
+            .await?;
+
+        images.push(ItemImage {
+            original: original_key,
+            thumbnail: thumbnail_key,
+        });
+    }
+
+    if images.is_empty() {
+        return Err(HandlerError::HandlerError(
+            StatusCode::BAD_REQUEST,
+            "No images uploaded".to_string(),
+        ));
+    }
+
     client
         .update_item()
+        .table_name(ITEM_TABLE)
         .key("sellerId", AttributeValue::S(claim.id))
         .key("id", AttributeValue::S(item_id.to_string()))
-        .update_expression("SET state = :archived")
-        .condition_expression("state = :inactive OR state = :failed")
-        .expression_attribute_values(":archived", ItemState::Active.into())
-        .expression_attribute_values(":inactive", ItemState::InActive.into())
-        .expression_attribute_values(":failed", ItemState::Failed.into())
+        .update_expression("SET images = list_append(if_not_exists(images, :empty), :new)")
+        .expression_attribute_values(":empty", AttributeValue::L(Vec::new()))
+        .expression_attribute_values(":new", to_attribute_value(&images)?)
         .send()
         .await?;
 
-    Ok(())
+    Ok(Json(images))
 }
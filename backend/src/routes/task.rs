@@ -0,0 +1,503 @@
+use std::sync::Arc;
+
+use aws_sdk_dynamodb::{
+    types::{AttributeValue, Put, TransactWriteItem, Update},
+    Client,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use lambda_http::tracing;
+use serde_dynamo::{from_item, from_items, to_attribute_value, to_item};
+use ulid::Ulid;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::{
+    constants::{BID_TABLE, BUYER_TABLE, ITEM_TABLE, PURCHASE_TABLE, SELLER_TABLE, TASK_TABLE},
+    errors::{is_update_condition_check_failed, HandlerError},
+    models::{
+        bid::{Bid, BidEvent, BidEventKind, Purchase},
+        item::{Item, ItemRef, ItemState, ItemTransition, ITEM_STATE_ATTR},
+        task::{Task, TaskKind, TaskStatus},
+    },
+    state::AppState,
+    transact::{is_transaction_canceled, transact_chunked},
+};
+
+pub fn router() -> OpenApiRouter<Arc<AppState>> {
+    OpenApiRouter::new()
+        .routes(routes!(list_tasks))
+        .routes(routes!(get_task))
+}
+
+/// Enqueue a task, used both by this module's own handlers and by other
+/// route handlers (e.g. `seller::seller_fulfill_item_by_id`) that need to
+/// schedule follow-up work for the background settlement worker.
+pub async fn put_task(client: &Client, task: &Task) -> Result<(), HandlerError> {
+    client
+        .put_item()
+        .table_name(TASK_TABLE)
+        .set_item(Some(to_item(task.clone())?))
+        .send()
+        .await?;
+    Ok(())
+}
+
+/// List all known tasks.
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "Task",
+    responses(
+        (status = OK, description = "Returns all tasks", body = Vec<Task>),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+    security(
+        ("http-jwt" = []),
+    ),
+)]
+async fn list_tasks(State(state): State<Arc<AppState>>) -> Result<Json<Vec<Task>>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+
+    let scan_resp = client.scan().table_name(TASK_TABLE).send().await?;
+    let tasks: Vec<Task> = from_items(scan_resp.items().to_vec())?;
+
+    Ok(Json(tasks))
+}
+
+/// Get a task by id.
+#[utoipa::path(
+    get,
+    path = "/{taskId}",
+    tag = "Task",
+    params(
+        ("taskId" = String, Path, description = "Task ID to get", format = Ulid),
+    ),
+    responses(
+        (status = OK, description = "Returns the task", body = Task),
+        (status = NOT_FOUND, description = "Task not found", body = HandlerError),
+        (status = INTERNAL_SERVER_ERROR, description = "Handler errors", body = HandlerError),
+    ),
+    security(
+        ("http-jwt" = []),
+    ),
+)]
+async fn get_task(
+    State(state): State<Arc<AppState>>,
+    Path(task_id): Path<Ulid>,
+) -> Result<Json<Task>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+
+    let get_resp = client
+        .get_item()
+        .table_name(TASK_TABLE)
+        .key("uid", AttributeValue::S(task_id.to_string()))
+        .send()
+        .await?;
+
+    let item = get_resp.item.ok_or(HandlerError::not_found())?;
+    let task: Task = from_item(item)?;
+
+    Ok(Json(task))
+}
+
+/// Scan for `Active` items whose auction has ended and enqueue a
+/// `CloseAuction` task for each one that doesn't already have one pending.
+///
+/// There's no GSI on `state`+`endDate` yet, so this falls back to a fully
+/// paginated table scan.
+pub async fn enqueue_expired_auctions(state: &AppState) -> Result<Vec<Task>, HandlerError> {
+    let client = Client::new(&state.aws_config);
+
+    let now = chrono::Local::now().timestamp_millis() as u64;
+
+    let mut expired = Vec::new();
+    let mut exclusive_start_key = None;
+    loop {
+        let mut req = client
+            .scan()
+            .table_name(ITEM_TABLE)
+            .filter_expression("#s = :active AND endDate <= :now")
+            .expression_attribute_names("#s", "state")
+            .expression_attribute_values(":active", ItemState::Active.into())
+            .expression_attribute_values(":now", AttributeValue::N(now.to_string()));
+        if let Some(key) = exclusive_start_key.take() {
+            req = req.set_exclusive_start_key(Some(key));
+        }
+        let resp = req.send().await?;
+        let page: Vec<Item> = from_items(resp.items().to_vec())?;
+        expired.extend(page);
+
+        exclusive_start_key = resp.last_evaluated_key().cloned();
+        if exclusive_start_key.is_none() {
+            break;
+        }
+    }
+
+    let pending: std::collections::HashSet<ItemRef> = list_enqueued_tasks(&client)
+        .await?
+        .into_iter()
+        .filter(|task| task.kind == TaskKind::CloseAuction)
+        .map(|task| task.item_ref)
+        .collect();
+
+    let mut enqueued = Vec::with_capacity(expired.len());
+    for item in expired {
+        let item_ref = ItemRef::from(&item);
+        if pending.contains(&item_ref) {
+            continue;
+        }
+        let task = Task::new(TaskKind::CloseAuction, item_ref);
+        put_task(&client, &task).await?;
+        enqueued.push(task);
+    }
+
+    Ok(enqueued)
+}
+
+/// Scan for tasks still waiting to be picked up.
+async fn list_enqueued_tasks(client: &Client) -> Result<Vec<Task>, HandlerError> {
+    let mut tasks = Vec::new();
+    let mut exclusive_start_key = None;
+    loop {
+        let mut req = client
+            .scan()
+            .table_name(TASK_TABLE)
+            .filter_expression("#s = :enqueued")
+            .expression_attribute_names("#s", "status")
+            .expression_attribute_values(":enqueued", to_attribute_value(TaskStatus::Enqueued)?);
+        if let Some(key) = exclusive_start_key.take() {
+            req = req.set_exclusive_start_key(Some(key));
+        }
+        let resp = req.send().await?;
+        let page: Vec<Task> = from_items(resp.items().to_vec())?;
+        tasks.extend(page);
+
+        exclusive_start_key = resp.last_evaluated_key().cloned();
+        if exclusive_start_key.is_none() {
+            break;
+        }
+    }
+
+    Ok(tasks)
+}
+
+/// One sweep of the background auction-settlement worker: enqueue
+/// `CloseAuction` tasks for items whose auction just ended, then drive every
+/// outstanding task to completion. `close_auction` conditions its writes on
+/// `state = :active`, so a task retried by an overlapping sweep (or a
+/// cancelled transaction) just finds nothing left to do rather than
+/// double-settling. `refund_losing_bids` skips bids it reads as already
+/// inactive; its read and its writes are not one conditional step.
+pub async fn run_settlement_sweep(state: &AppState) -> Result<(), HandlerError> {
+    enqueue_expired_auctions(state).await?;
+
+    let client = Client::new(&state.aws_config);
+    for task in list_enqueued_tasks(&client).await? {
+        if let Err(e) = process_task(state, task).await {
+            tracing::warn!("auction settlement task failed: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Process a single task, driving it to `Succeeded` or `Failed`.
+pub async fn process_task(state: &AppState, mut task: Task) -> Result<Task, HandlerError> {
+    let client = Client::new(&state.aws_config);
+
+    task.status = TaskStatus::Processing;
+    task.started_at = Some(chrono::Local::now().timestamp_millis() as u64);
+    put_task(&client, &task).await?;
+
+    let result = match task.kind {
+        TaskKind::CloseAuction => close_auction(&client, state, &task.item_ref).await,
+        TaskKind::SettleBid => Err(HandlerError::HandlerError(
+            axum::http::StatusCode::NOT_IMPLEMENTED,
+            "SettleBid is handled inline by CloseAuction".to_string(),
+        )),
+        TaskKind::RefundBid => refund_losing_bids(&client, &task.item_ref).await,
+    };
+
+    task.finished_at = Some(chrono::Local::now().timestamp_millis() as u64);
+    match result {
+        Ok(()) => task.status = TaskStatus::Succeeded,
+        Err(e) => {
+            task.status = TaskStatus::Failed;
+            task.error = Some(e.to_string());
+        }
+    }
+    put_task(&client, &task).await?;
+
+    Ok(task)
+}
+
+/// Settle an `Active` item whose auction has ended: if it has a winning bid,
+/// run the same winning-bid transaction `seller::seller_fulfill_item_by_id`
+/// runs manually (record `soldBid`/`soldPrice`/`soldTime`, credit the
+/// seller, debit the winning bidder's hold, record the `Purchase`, and
+/// enqueue one `RefundBid` task that refunds the losing bids) and move it to
+/// `Completed`, otherwise move it to `Failed`. Both paths are
+/// `ItemTransition`-guarded (or, for the winning path, guarded via the same
+/// condition inside a `transact_write_items`) on the item still being
+/// `Active`, so a task retried by an overlapping sweep just finds the guard
+/// already tripped and treats it as already-settled.
+async fn close_auction(
+    client: &Client,
+    state: &AppState,
+    item_ref: &ItemRef,
+) -> Result<(), HandlerError> {
+    let get_item_resp = client
+        .get_item()
+        .table_name(ITEM_TABLE)
+        .key("sellerId", AttributeValue::S(item_ref.seller_id.clone()))
+        .key("id", AttributeValue::S(item_ref.id.to_string()))
+        .send()
+        .await?;
+    let item: Item = from_item(get_item_resp.item.ok_or(HandlerError::not_found())?)?;
+
+    if item.state != ItemState::Active {
+        // Already settled by this task or a concurrent fulfill, nothing to do.
+        return Ok(());
+    }
+
+    let Some(winning_bid_ref) = item.current_bid.clone() else {
+        if apply_item_transition(client, item_ref, ItemTransition::SettleUnsold).await? {
+            state.bid_events.publish(
+                item_ref,
+                BidEvent {
+                    kind: BidEventKind::AuctionEnded,
+                    item: item_ref.clone(),
+                    current_bid: None,
+                    amount: None,
+                    target_buyer_id: None,
+                },
+            );
+        }
+        return Ok(());
+    };
+
+    let get_bid_resp = client
+        .get_item()
+        .table_name(BID_TABLE)
+        .key("buyerId", AttributeValue::S(winning_bid_ref.buyer_id.clone()))
+        .key("id", AttributeValue::S(winning_bid_ref.id.to_string()))
+        .send()
+        .await?;
+    let bid: Bid = from_item(get_bid_resp.item.ok_or(HandlerError::not_found())?)?;
+
+    let seller_income = ((bid.amount as f64) * 0.95).floor() as u64;
+    let purchase = Purchase {
+        buyer_id: bid.buyer_id.clone(),
+        id: Ulid::new(),
+        create_at: chrono::Local::now().timestamp_millis() as u64,
+        item: item_ref.clone(),
+        price: bid.amount,
+        sold_time: bid.create_at,
+    };
+
+    let (transition_condition, guard_values) = ItemTransition::Settle.guard();
+    let mut update_item_cmd = Update::builder()
+        .table_name(ITEM_TABLE)
+        .key("sellerId", AttributeValue::S(item_ref.seller_id.clone()))
+        .key("id", AttributeValue::S(item_ref.id.to_string()))
+        .update_expression(
+            "SET soldBid = :bid_ref, soldTime = :time, soldPrice = :price, #state = :toState",
+        )
+        .condition_expression(transition_condition)
+        .expression_attribute_names("#state", ITEM_STATE_ATTR)
+        .expression_attribute_values(":bid_ref", to_attribute_value(winning_bid_ref.clone())?)
+        .expression_attribute_values(":time", to_attribute_value(bid.create_at)?)
+        .expression_attribute_values(":price", to_attribute_value(bid.amount)?);
+    for (key, value) in guard_values {
+        update_item_cmd = update_item_cmd.expression_attribute_values(key, value);
+    }
+    let update_item = TransactWriteItem::builder()
+        .update(update_item_cmd.build()?)
+        .build();
+
+    let credit_seller = TransactWriteItem::builder()
+        .update(
+            Update::builder()
+                .table_name(SELLER_TABLE)
+                .key("id", AttributeValue::S(item_ref.seller_id.clone()))
+                .update_expression("SET fund = fund + :amount")
+                .expression_attribute_values(":amount", to_attribute_value(seller_income)?)
+                .build()?,
+        )
+        .build();
+
+    let debit_buyer = TransactWriteItem::builder()
+        .update(
+            Update::builder()
+                .table_name(BUYER_TABLE)
+                .key("id", AttributeValue::S(bid.buyer_id.clone()))
+                .update_expression("SET fundOnHold = fundOnHold - :amount")
+                .condition_expression("fundOnHold >= :amount")
+                // `buyer_place_bid` holds the hidden `max_amount`, not the
+                // visible clearing `amount`, so the settlement release must
+                // match it or the difference is stranded in fundOnHold forever.
+                .expression_attribute_values(":amount", to_attribute_value(bid.max_amount)?)
+                .build()?,
+        )
+        .build();
+
+    let deactivate_bid = TransactWriteItem::builder()
+        .update(
+            Update::builder()
+                .table_name(BID_TABLE)
+                .key("buyerId", AttributeValue::S(winning_bid_ref.buyer_id.clone()))
+                .key("id", AttributeValue::S(winning_bid_ref.id.to_string()))
+                .update_expression("SET isActive = :false")
+                .expression_attribute_values(":false", AttributeValue::Bool(false))
+                .build()?,
+        )
+        .build();
+
+    let put_purchase = TransactWriteItem::builder()
+        .put(
+            Put::builder()
+                .table_name(PURCHASE_TABLE)
+                .set_item(Some(to_item(purchase)?))
+                .build()?,
+        )
+        .build();
+
+    match transact_chunked(
+        client,
+        vec![
+            update_item,
+            credit_seller,
+            debit_buyer,
+            deactivate_bid,
+            put_purchase,
+        ],
+    )
+    .await
+    {
+        Ok(_) => {
+            if !item.past_bids.is_empty() {
+                put_task(client, &Task::new(TaskKind::RefundBid, item_ref.clone())).await?;
+            }
+            state.bid_events.publish(
+                item_ref,
+                BidEvent {
+                    kind: BidEventKind::Won,
+                    item: item_ref.clone(),
+                    current_bid: Some(winning_bid_ref),
+                    amount: Some(bid.amount),
+                    target_buyer_id: Some(bid.buyer_id.clone()),
+                },
+            );
+            state.bid_events.publish(
+                item_ref,
+                BidEvent {
+                    kind: BidEventKind::AuctionEnded,
+                    item: item_ref.clone(),
+                    current_bid: None,
+                    amount: None,
+                    target_buyer_id: None,
+                },
+            );
+        }
+        Err(e) if is_transaction_canceled(&e) => {
+            // Already settled by this task or a concurrent fulfill, nothing to do.
+        }
+        Err(e) => return Err(e),
+    }
+
+    Ok(())
+}
+
+/// Apply an `ItemTransition` to `item_ref`'s `state`. Returns `true` if the
+/// transition committed, `false` if its guard rejected the write because the
+/// item had already moved on (treated as a no-op rather than an error, since
+/// callers only apply a transition once per task).
+async fn apply_item_transition(
+    client: &Client,
+    item_ref: &ItemRef,
+    transition: ItemTransition,
+) -> Result<bool, HandlerError> {
+    let (condition, guard_values) = transition.guard();
+
+    let mut cmd = client
+        .update_item()
+        .table_name(ITEM_TABLE)
+        .key("sellerId", AttributeValue::S(item_ref.seller_id.clone()))
+        .key("id", AttributeValue::S(item_ref.id.to_string()))
+        .update_expression("SET #state = :toState")
+        .condition_expression(condition)
+        .expression_attribute_names("#state", ITEM_STATE_ATTR);
+    for (key, value) in guard_values {
+        cmd = cmd.expression_attribute_values(key, value);
+    }
+
+    match cmd.send().await {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            let err: HandlerError = e.into();
+            if is_update_condition_check_failed(&err) {
+                Ok(false)
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Release held funds back to every losing bidder on an item, skipping the
+/// bid that was promoted to `sold_bid`.
+async fn refund_losing_bids(client: &Client, item_ref: &ItemRef) -> Result<(), HandlerError> {
+    let get_item_resp = client
+        .get_item()
+        .table_name(ITEM_TABLE)
+        .key("sellerId", AttributeValue::S(item_ref.seller_id.clone()))
+        .key("id", AttributeValue::S(item_ref.id.to_string()))
+        .send()
+        .await?;
+    let item: Item = from_item(get_item_resp.item.ok_or(HandlerError::not_found())?)?;
+
+    for bid_ref in &item.past_bids {
+        if item.sold_bid.as_ref() == Some(bid_ref) {
+            continue;
+        }
+
+        let get_bid_resp = client
+            .get_item()
+            .table_name(BID_TABLE)
+            .key("buyerId", AttributeValue::S(bid_ref.buyer_id.clone()))
+            .key("id", AttributeValue::S(bid_ref.id.to_string()))
+            .send()
+            .await?;
+        let Some(bid_item) = get_bid_resp.item else {
+            continue;
+        };
+        let bid: Bid = from_item(bid_item)?;
+        if !bid.is_active {
+            continue;
+        }
+
+        client
+            .update_item()
+            .table_name(BID_TABLE)
+            .key("buyerId", AttributeValue::S(bid_ref.buyer_id.clone()))
+            .key("id", AttributeValue::S(bid_ref.id.to_string()))
+            .update_expression("SET isActive = :false")
+            .expression_attribute_values(":false", AttributeValue::Bool(false))
+            .send()
+            .await?;
+
+        client
+            .update_item()
+            .table_name(BUYER_TABLE)
+            .key("id", AttributeValue::S(bid_ref.buyer_id.clone()))
+            .update_expression("SET fund = fund + :amount, fundOnHold = fundOnHold - :amount")
+            .condition_expression("fundOnHold >= :amount")
+            .expression_attribute_values(":amount", serde_dynamo::to_attribute_value(bid.max_amount)?)
+            .send()
+            .await?;
+    }
+
+    Ok(())
+}
@@ -1,12 +1,89 @@
-use std::env;
+use std::{collections::HashMap, env, sync::Arc, time::Duration};
 
 use aws_config::{BehaviorVersion, Region, SdkConfig};
+use base64::{prelude::BASE64_STANDARD, Engine};
 use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header};
 use lambda_http::Error;
+use moka::future::Cache;
+use opaque_ke::ServerSetup;
+
+use crate::{
+    constants::ACCESS_TOKEN_CACHE_TTL_SECS,
+    events::BidEventBus,
+    gateway::{DynamoGateway, Gateway, InMemoryGateway},
+    jwks::JwksStore,
+    mailer::{Mailer, NoopMailer, SesMailer},
+    models::oauth::OAuthProviderConfig,
+    opaque::DefaultCipherSuite,
+};
 
 pub struct AppState {
     pub aws_config: SdkConfig,
     pub jwt: (EncodingKey, DecodingKey, Header),
+    pub s3: aws_sdk_s3::Client,
+    pub opaque_setup: ServerSetup<DefaultCipherSuite>,
+    /// Cached jti -> valid verdicts, consulted before falling back to `ACCESS_TOKEN_TABLE`.
+    pub token_cache: Cache<String, bool>,
+    /// Live per-item bid-event channels backing `/buyer/bid-stream` and
+    /// `/item/{sellerId}/{itemId}/events`.
+    pub bid_events: BidEventBus,
+    /// Storage handlers use for reads/writes that need to be testable
+    /// without live AWS. See `gateway` for the table.
+    pub gateway: Arc<dyn Gateway>,
+    /// `kid` -> asymmetric `DecodingKey` map for JWKS-backed verification in
+    /// `auth_middleware`, kept fresh by `jwks::spawn_jwks_refresh` when
+    /// `JWKS_URL` is configured. Empty (and harmless) otherwise.
+    pub jwks: Arc<JwksStore>,
+    /// Sends the email-verification link minted by `register_finish`.
+    pub mailer: Arc<dyn Mailer>,
+    /// Configured OAuth2 social-login providers, keyed by name (e.g. `"google"`),
+    /// populated from `OAUTH_PROVIDERS`/`OAUTH_<NAME>_*` env vars. Empty (and
+    /// harmless; `/v1/oauth/*` just 404s) when `OAUTH_PROVIDERS` isn't set.
+    pub oauth_providers: HashMap<String, OAuthProviderConfig>,
+}
+
+fn new_token_cache() -> Cache<String, bool> {
+    Cache::builder()
+        .time_to_live(Duration::from_secs(ACCESS_TOKEN_CACHE_TTL_SECS))
+        .build()
+}
+
+fn load_opaque_setup() -> Result<ServerSetup<DefaultCipherSuite>, Error> {
+    let encoded = env::var("OPAQUE_SERVER_SETUP").map_err(|e| e.to_string())?;
+    let bytes = BASE64_STANDARD
+        .decode(encoded)
+        .map_err(|e| e.to_string())?;
+    ServerSetup::<DefaultCipherSuite>::deserialize(&bytes).map_err(|e| e.to_string().into())
+}
+
+/// Parse `OAUTH_PROVIDERS` (a comma-separated list of provider names) plus
+/// each provider's `OAUTH_<NAME>_*` variables into a name -> config map.
+/// Unset entirely (the default), this is just an empty map, mirroring how an
+/// unset `JWKS_URL` leaves `jwks` empty rather than failing startup.
+fn load_oauth_providers() -> Result<HashMap<String, OAuthProviderConfig>, Error> {
+    let Ok(providers) = env::var("OAUTH_PROVIDERS") else {
+        return Ok(HashMap::new());
+    };
+
+    let mut configs = HashMap::new();
+    for name in providers.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let prefix = format!("OAUTH_{}", name.to_uppercase());
+        let var = |suffix: &str| env::var(format!("{prefix}_{suffix}")).map_err(|e| e.to_string());
+
+        configs.insert(
+            name.to_string(),
+            OAuthProviderConfig {
+                client_id: var("CLIENT_ID")?,
+                client_secret: var("CLIENT_SECRET")?,
+                auth_url: var("AUTH_URL")?,
+                token_url: var("TOKEN_URL")?,
+                userinfo_url: var("USERINFO_URL")?,
+                redirect_url: var("REDIRECT_URL")?,
+            },
+        );
+    }
+
+    Ok(configs)
 }
 
 impl AppState {
@@ -16,14 +93,26 @@ impl AppState {
             .load()
             .await;
         let secret = env::var("JWT_SECRET").map_err(|e| e.to_string())?;
+        let ses_from_address = env::var("SES_FROM_EMAIL").map_err(|e| e.to_string())?;
 
         Ok(Self {
+            s3: aws_sdk_s3::Client::new(&config),
+            gateway: Arc::new(DynamoGateway::new(aws_sdk_dynamodb::Client::new(&config))),
+            mailer: Arc::new(SesMailer::new(
+                aws_sdk_sesv2::Client::new(&config),
+                ses_from_address,
+            )),
             aws_config: config,
             jwt: (
                 EncodingKey::from_base64_secret(&secret)?,
                 DecodingKey::from_base64_secret(&secret)?,
                 Header::new(Algorithm::HS256),
             ),
+            opaque_setup: load_opaque_setup()?,
+            token_cache: new_token_cache(),
+            bid_events: BidEventBus::new(),
+            jwks: Arc::new(JwksStore::default()),
+            oauth_providers: load_oauth_providers()?,
         })
     }
 
@@ -36,12 +125,29 @@ impl AppState {
         let secret = env::var("JWT_SECRET").map_err(|e| e.to_string())?;
 
         Ok(Self {
+            s3: aws_sdk_s3::Client::new(&config),
+            gateway: Arc::new(DynamoGateway::new(aws_sdk_dynamodb::Client::new(&config))),
+            mailer: Arc::new(NoopMailer),
             aws_config: config,
             jwt: (
                 EncodingKey::from_base64_secret(&secret)?,
                 DecodingKey::from_base64_secret(&secret)?,
                 Header::new(Algorithm::HS256),
             ),
+            opaque_setup: load_opaque_setup()?,
+            token_cache: new_token_cache(),
+            bid_events: BidEventBus::new(),
+            jwks: Arc::new(JwksStore::default()),
+            oauth_providers: load_oauth_providers()?,
         })
     }
+
+    /// Like `test`, but backed by an `InMemoryGateway` instead of a live
+    /// local DynamoDB endpoint, for handler tests that only go through
+    /// `state.gateway`.
+    pub async fn test_in_memory() -> Result<Self, Error> {
+        let mut state = Self::test().await?;
+        state.gateway = Arc::new(InMemoryGateway::default());
+        Ok(state)
+    }
 }
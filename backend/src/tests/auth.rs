@@ -11,12 +11,9 @@ use ulid::Ulid;
 use crate::{
     constants::{BUYER_TABLE, SELLER_TABLE},
     create_service,
-    models::{
-        auth::{LoginPayload, RegisterPayload},
-        user::{UserInfo, UserType},
-    },
+    models::user::UserType,
     state::AppState,
-    tests::parse_resp,
+    tests::{build_request, opaque_login, opaque_register},
     utils::create_userid,
 };
 
@@ -59,58 +56,54 @@ async fn test_oneshot() -> Result<(), Error> {
 async fn test_auth_login() -> Result<(), Error> {
     let state = Arc::new(AppState::new().await?);
     let random_email = format!("test_seller_{}@test.com", Ulid::new());
-    let password_str = Ulid::new();
-    {
-        let service = create_service(state.clone()).await?;
-
-        let register_payload = RegisterPayload {
-            first_name: "John".to_string(),
-            last_name: "Doe".to_string(),
-            email: random_email.clone(),
-            user_type: UserType::Seller,
-            password: password_str.to_string(),
-        };
-
-        let payload: String = serde_json::to_string(&register_payload)?;
-
-        let req = Request::builder()
-            .method("POST")
-            .header("Content-Type", "application/json")
-            .uri("/v1/register")
-            .body(payload)?;
-
-        let resp = service.oneshot(req).await?;
-
-        assert_eq!(resp.status(), StatusCode::OK);
-
-        let user_info: UserInfo = parse_resp(resp).await?;
-        assert_eq!(user_info.email.as_str(), random_email.as_str());
-    }
-
-    // Login
-    {
-        let service = create_service(state.clone()).await?;
-
-        let answer = LoginPayload {
-            email: random_email.clone(),
-            user_type: UserType::Seller,
-            password: password_str.to_string(),
-        };
-        let payload: String = serde_json::to_string(&answer)?;
-
-        let req = Request::builder()
-            .method("POST")
-            .header("Content-Type", "application/json")
-            .uri("/v1/login")
-            .body(payload)?;
-
-        let resp = service.oneshot(req).await?;
-
-        assert_eq!(resp.status(), StatusCode::OK);
-
-        let user_info: UserInfo = parse_resp(resp).await?;
-        assert_eq!(user_info.email.as_str(), random_email.as_str());
-    }
+    let password_str = Ulid::new().to_string();
+
+    let user_info = opaque_register(
+        state.clone(),
+        "John",
+        "Doe",
+        &random_email,
+        UserType::Seller,
+        &password_str,
+    )
+    .await?;
+    assert_eq!(user_info.email.as_str(), random_email.as_str());
+
+    let user_info = opaque_login(state.clone(), &random_email, UserType::Seller, &password_str).await?;
+    assert_eq!(user_info.email.as_str(), random_email.as_str());
+
+    clean_account(state, random_email, UserType::Seller).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_logout_revokes_token() -> Result<(), Error> {
+    let state = Arc::new(AppState::new().await?);
+    let random_email = format!("test_seller_{}@test.com", Ulid::new());
+    let password_str = Ulid::new().to_string();
+
+    let user_info = opaque_register(
+        state.clone(),
+        "John",
+        "Doe",
+        &random_email,
+        UserType::Seller,
+        &password_str,
+    )
+    .await?;
+
+    let token = user_info.token.as_deref().expect("opaque_register logs in for real, so a token is always present");
+
+    let service = create_service(state.clone()).await?;
+    let req = build_request::<()>("POST", "/v1/auth/logout", token, None)?;
+    let resp = service.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let service = create_service(state.clone()).await?;
+    let req = build_request::<()>("GET", "/v1/ping", token, None)?;
+    let resp = service.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
 
     clean_account(state, random_email, UserType::Seller).await?;
 
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use lambda_http::Error;
+
+use crate::{
+    constants::{BUYER_TABLE, ITEM_TABLE, SELLER_TABLE},
+    gateway::{is_gateway_condition_check_failed, Gateway, InMemoryGateway, WriteOp},
+    models::item::{ItemState, ItemTransition, ITEM_STATE_ATTR},
+};
+
+fn seller_key(id: &str) -> HashMap<String, AttributeValue> {
+    HashMap::from([("id".to_string(), AttributeValue::S(id.to_string()))])
+}
+
+fn buyer_key(id: &str) -> HashMap<String, AttributeValue> {
+    HashMap::from([("id".to_string(), AttributeValue::S(id.to_string()))])
+}
+
+#[tokio::test]
+async fn test_transaction_commits_every_write() -> Result<(), Error> {
+    let gateway = InMemoryGateway::default();
+
+    gateway
+        .put_item(
+            SELLER_TABLE,
+            HashMap::from([
+                ("id".to_string(), AttributeValue::S("seller-1".to_string())),
+                ("fund".to_string(), AttributeValue::N("0".to_string())),
+            ]),
+        )
+        .await?;
+    gateway
+        .put_item(
+            BUYER_TABLE,
+            HashMap::from([
+                ("id".to_string(), AttributeValue::S("buyer-1".to_string())),
+                ("fundOnHold".to_string(), AttributeValue::N("100".to_string())),
+            ]),
+        )
+        .await?;
+
+    gateway
+        .transaction(vec![
+            WriteOp::Update {
+                table: SELLER_TABLE,
+                key: seller_key("seller-1"),
+                update_expression: "SET fund = fund + :amount".to_string(),
+                condition_expression: None,
+                names: HashMap::new(),
+                values: HashMap::from([(":amount".to_string(), AttributeValue::N("95".to_string()))]),
+            },
+            WriteOp::Update {
+                table: BUYER_TABLE,
+                key: buyer_key("buyer-1"),
+                update_expression: "SET fundOnHold = fundOnHold - :amount".to_string(),
+                condition_expression: Some("fundOnHold >= :amount".to_string()),
+                names: HashMap::new(),
+                values: HashMap::from([(":amount".to_string(), AttributeValue::N("100".to_string()))]),
+            },
+        ])
+        .await?;
+
+    let seller = gateway.get_item(SELLER_TABLE, seller_key("seller-1")).await?.unwrap();
+    assert_eq!(seller.get("fund"), Some(&AttributeValue::N("95".to_string())));
+
+    let buyer = gateway.get_item(BUYER_TABLE, buyer_key("buyer-1")).await?.unwrap();
+    assert_eq!(buyer.get("fundOnHold"), Some(&AttributeValue::N("0".to_string())));
+
+    Ok(())
+}
+
+/// Mirrors the fund-transfer guard in `seller::seller_fulfill_item_by_id`:
+/// `fundOnHold >= :amount` rejects, so neither write should have applied.
+#[tokio::test]
+async fn test_transaction_rolls_back_on_condition_failure() -> Result<(), Error> {
+    let gateway = InMemoryGateway::default();
+
+    gateway
+        .put_item(
+            SELLER_TABLE,
+            HashMap::from([
+                ("id".to_string(), AttributeValue::S("seller-1".to_string())),
+                ("fund".to_string(), AttributeValue::N("0".to_string())),
+            ]),
+        )
+        .await?;
+    gateway
+        .put_item(
+            BUYER_TABLE,
+            HashMap::from([
+                ("id".to_string(), AttributeValue::S("buyer-1".to_string())),
+                ("fundOnHold".to_string(), AttributeValue::N("10".to_string())),
+            ]),
+        )
+        .await?;
+
+    let result = gateway
+        .transaction(vec![
+            WriteOp::Update {
+                table: SELLER_TABLE,
+                key: seller_key("seller-1"),
+                update_expression: "SET fund = fund + :amount".to_string(),
+                condition_expression: None,
+                names: HashMap::new(),
+                values: HashMap::from([(":amount".to_string(), AttributeValue::N("95".to_string()))]),
+            },
+            WriteOp::Update {
+                table: BUYER_TABLE,
+                key: buyer_key("buyer-1"),
+                update_expression: "SET fundOnHold = fundOnHold - :amount".to_string(),
+                condition_expression: Some("fundOnHold >= :amount".to_string()),
+                names: HashMap::new(),
+                values: HashMap::from([(":amount".to_string(), AttributeValue::N("100".to_string()))]),
+            },
+        ])
+        .await;
+
+    let err = result.expect_err("fundOnHold >= :amount should reject the write");
+    assert!(is_gateway_condition_check_failed(&err));
+
+    let seller = gateway.get_item(SELLER_TABLE, seller_key("seller-1")).await?.unwrap();
+    assert_eq!(seller.get("fund"), Some(&AttributeValue::N("0".to_string())));
+
+    let buyer = gateway.get_item(BUYER_TABLE, buyer_key("buyer-1")).await?.unwrap();
+    assert_eq!(buyer.get("fundOnHold"), Some(&AttributeValue::N("10".to_string())));
+
+    Ok(())
+}
+
+/// Exercises an `ItemTransition` guard (see `models::item`) through the
+/// gateway's condition evaluator, including its missing-attribute-as-null
+/// and empty-list semantics.
+#[tokio::test]
+async fn test_transaction_respects_item_transition_guard() -> Result<(), Error> {
+    let gateway = InMemoryGateway::default();
+    let key = HashMap::from([
+        ("sellerId".to_string(), AttributeValue::S("seller-1".to_string())),
+        ("id".to_string(), AttributeValue::S("item-1".to_string())),
+    ]);
+
+    gateway
+        .put_item(
+            ITEM_TABLE,
+            HashMap::from([
+                ("sellerId".to_string(), AttributeValue::S("seller-1".to_string())),
+                ("id".to_string(), AttributeValue::S("item-1".to_string())),
+                (ITEM_STATE_ATTR.to_string(), AttributeValue::S(ItemState::Active.to_string())),
+            ]),
+        )
+        .await?;
+
+    let (condition, guard_values) = ItemTransition::Unpublish.guard();
+    let mut values: HashMap<String, AttributeValue> = guard_values.into_iter().collect();
+    values.insert(":null".to_string(), AttributeValue::Null(true));
+    values.insert(":zero".to_string(), AttributeValue::N("0".to_string()));
+
+    gateway
+        .transaction(vec![WriteOp::Update {
+            table: ITEM_TABLE,
+            key: key.clone(),
+            update_expression: "SET #state = :toState".to_string(),
+            condition_expression: Some(format!(
+                "({condition}) AND currentBid = :null AND size(pastBids) = :zero"
+            )),
+            names: HashMap::from([("#state".to_string(), ITEM_STATE_ATTR.to_string())]),
+            values,
+        }])
+        .await?;
+
+    let item = gateway.get_item(ITEM_TABLE, key).await?.unwrap();
+    assert_eq!(
+        item.get(ITEM_STATE_ATTR),
+        Some(&AttributeValue::S(ItemState::InActive.to_string()))
+    );
+
+    Ok(())
+}
@@ -28,7 +28,12 @@ async fn test_get_item() -> Result<(), Error> {
     let service = create_service(state.clone()).await?;
 
     let uri = format!("/v1/item/{}/{}", &user_info.id, item_ref.id.to_string());
-    let req = build_request::<()>("GET", &uri, &user_info.token, None)?;
+    let req = build_request::<()>(
+        "GET",
+        &uri,
+        user_info.token.as_deref().expect("caller is logged in, so a token is always present"),
+        None,
+    )?;
     let resp = service.oneshot(req).await?;
 
     assert_eq!(resp.status(), StatusCode::OK);
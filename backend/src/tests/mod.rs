@@ -1,15 +1,38 @@
 mod auth;
+mod gateway;
 mod item;
 mod seller;
 
+use std::sync::Arc;
+
+use aws_sdk_dynamodb::{types::AttributeValue, Client};
 use axum::{
     body::{Body, HttpBody},
     extract::Request,
     response::Response,
 };
-use lambda_http::Error;
+use lambda_http::{tower::ServiceExt, Error};
+use opaque_ke::{
+    rand::rngs::OsRng, ClientLogin, ClientLoginFinishParameters, ClientRegistration,
+    ClientRegistrationFinishParameters, CredentialResponse, RegistrationResponse,
+};
 use serde::{de::DeserializeOwned, Serialize};
 
+use crate::{
+    constants::{VERIFICATION_TABLE, VERIFICATION_USER_INDEX},
+    create_service,
+    models::{
+        auth::{
+            LoginFinishRequest, LoginStartRequest, LoginStartResponse, RegistrationFinishRequest,
+            RegistrationStartRequest, RegistrationStartResponse,
+        },
+        user::{UserInfo, UserType},
+    },
+    opaque::DefaultCipherSuite,
+    state::AppState,
+    utils::{hex_decode, hex_encode},
+};
+
 async fn parse_resp<T: DeserializeOwned>(resp: Response<Body>) -> Result<T, Error> {
     let body = resp.into_body();
     let limit = body.size_hint().upper().unwrap_or(u64::MAX) as usize;
@@ -43,3 +66,136 @@ fn build_request<T: Serialize>(
     }?;
     Ok(req)
 }
+
+/// Drive a full client-side OPAQUE registration against `/v1/register/{start,finish}`.
+///
+/// OPAQUE envelopes can't be hand-rolled into seed data, so tests that need an account
+/// register one for real instead of relying on a pre-seeded fixture.
+async fn opaque_register(
+    state: Arc<AppState>,
+    first_name: &str,
+    last_name: &str,
+    email: &str,
+    user_type: UserType,
+    password: &str,
+) -> Result<UserInfo, Error> {
+    let mut rng = OsRng;
+    let start_result = ClientRegistration::<DefaultCipherSuite>::start(&mut rng, password.as_bytes())?;
+
+    let start_req = RegistrationStartRequest {
+        email: email.to_string(),
+        user_type,
+        registration_request: hex_encode(&start_result.message.serialize()),
+    };
+    let service = create_service(state.clone()).await?;
+    let req = build_request("POST", "/v1/register/start", "", Some(start_req))?;
+    let resp = service.oneshot(req).await?;
+    let start_resp: RegistrationStartResponse = parse_resp(resp).await?;
+
+    let registration_response = RegistrationResponse::<DefaultCipherSuite>::deserialize(
+        &hex_decode(&start_resp.registration_response)?,
+    )?;
+    let finish_result = start_result.state.finish(
+        &mut rng,
+        password.as_bytes(),
+        registration_response,
+        ClientRegistrationFinishParameters::default(),
+    )?;
+
+    let finish_req = RegistrationFinishRequest {
+        first_name: first_name.to_string(),
+        last_name: last_name.to_string(),
+        email: email.to_string(),
+        user_type,
+        registration_upload: hex_encode(&finish_result.message.serialize()),
+        invite_secret: None,
+    };
+    let service = create_service(state.clone()).await?;
+    let req = build_request("POST", "/v1/register/finish", "", Some(finish_req))?;
+    let resp = service.oneshot(req).await?;
+    let user_info: UserInfo = parse_resp(resp).await?;
+
+    activate_test_account(state.clone(), &user_info.id).await?;
+
+    // `register_finish` no longer mints a usable token/refresh_token for the
+    // still-inactive account it just created, so log in for real now that
+    // activation has flipped `is_active`, the same way a verified user would.
+    opaque_login(state, email, user_type, password).await
+}
+
+/// `register_finish` leaves new accounts inactive pending email confirmation,
+/// so tests have no mailbox to read the link from. Look the token up by the
+/// `VERIFICATION_USER_INDEX` GSI and hit `/v1/verify/{token}` directly, the
+/// same way a real user would click through from the email.
+async fn activate_test_account(state: Arc<AppState>, user_id: &str) -> Result<(), Error> {
+    let client = Client::new(&state.aws_config);
+    let resp = client
+        .query()
+        .table_name(VERIFICATION_TABLE)
+        .index_name(VERIFICATION_USER_INDEX)
+        .key_condition_expression("userId = :uid")
+        .expression_attribute_values(":uid", AttributeValue::S(user_id.to_string()))
+        .send()
+        .await?;
+    let token = resp
+        .items()
+        .first()
+        .and_then(|item| item.get("id"))
+        .and_then(|v| v.as_s().ok())
+        .cloned()
+        .ok_or("no verification token found for test account")?;
+
+    let service = create_service(state).await?;
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/verify/{token}"))
+        .body(Body::empty())?;
+    let resp = service.oneshot(req).await?;
+    if resp.status() != axum::http::StatusCode::OK {
+        return Err("failed to activate test account".into());
+    }
+
+    Ok(())
+}
+
+/// Drive a full client-side OPAQUE login against `/v1/login/{start,finish}`.
+async fn opaque_login(
+    state: Arc<AppState>,
+    email: &str,
+    user_type: UserType,
+    password: &str,
+) -> Result<UserInfo, Error> {
+    let mut rng = OsRng;
+    let start_result = ClientLogin::<DefaultCipherSuite>::start(&mut rng, password.as_bytes())?;
+
+    let start_req = LoginStartRequest {
+        email: email.to_string(),
+        user_type,
+        credential_request: hex_encode(&start_result.message.serialize()),
+    };
+    let service = create_service(state.clone()).await?;
+    let req = build_request("POST", "/v1/login/start", "", Some(start_req))?;
+    let resp = service.oneshot(req).await?;
+    let start_resp: LoginStartResponse = parse_resp(resp).await?;
+
+    let credential_response = CredentialResponse::<DefaultCipherSuite>::deserialize(
+        &hex_decode(&start_resp.credential_response)?,
+    )?;
+    let finish_result = start_result.state.finish(
+        password.as_bytes(),
+        credential_response,
+        ClientLoginFinishParameters::default(),
+    )?;
+
+    let finish_req = LoginFinishRequest {
+        email: email.to_string(),
+        user_type,
+        credential_finalization: hex_encode(&finish_result.message.serialize()),
+    };
+    let service = create_service(state.clone()).await?;
+    let req = build_request("POST", "/v1/login/finish", "", Some(finish_req))?;
+    let resp = service.oneshot(req).await?;
+    let user_info: UserInfo = parse_resp(resp).await?;
+
+    Ok(user_info)
+}
@@ -1,11 +1,10 @@
 use std::sync::Arc;
 
 use aws_sdk_dynamodb::{
-    operation::transact_write_items::builders::TransactWriteItemsFluentBuilder,
     types::{AttributeValue, Delete, TransactWriteItem},
     Client,
 };
-use axum::http::{Request, StatusCode};
+use axum::http::StatusCode;
 use chrono::TimeDelta;
 use lambda_http::{tower::ServiceExt, Error};
 use serde_dynamo::from_items;
@@ -15,12 +14,12 @@ use crate::{
     constants::ITEM_TABLE,
     create_service,
     models::{
-        auth::LoginPayload,
         item::{AddItemRequest, Item, ItemRef},
         user::{UserInfo, UserType},
     },
     state::AppState,
-    tests::{build_request, parse_resp},
+    tests::{build_request, opaque_login, opaque_register, parse_resp},
+    transact::transact_chunked,
 };
 
 const TEST_SELLER_EMAIL: &str = "foo@test.org";
@@ -53,25 +52,22 @@ pub async fn clean_items(state: Arc<AppState>, id: String) -> Result<(), Error>
 
     let items: Vec<Item> = from_items(query_resp.items().to_vec())?;
 
-    let transactions = items.into_iter().try_fold(
-        client.transact_write_items(),
-        |acc, item| -> Result<TransactWriteItemsFluentBuilder, Error> {
-            let nacc = acc.transact_items(
-                TransactWriteItem::builder()
-                    .delete(
-                        Delete::builder()
-                            .table_name(ITEM_TABLE)
-                            .key("sellerId", AttributeValue::S(id.clone()))
-                            .key("id", AttributeValue::S(item.id.to_string()))
-                            .build()?,
-                    )
-                    .build(),
-            );
-            Ok(nacc)
-        },
-    )?;
-
-    transactions.send().await?;
+    let deletes = items
+        .into_iter()
+        .map(|item| -> Result<TransactWriteItem, Error> {
+            Ok(TransactWriteItem::builder()
+                .delete(
+                    Delete::builder()
+                        .table_name(ITEM_TABLE)
+                        .key("sellerId", AttributeValue::S(id.clone()))
+                        .key("id", AttributeValue::S(item.id.to_string()))
+                        .build()?,
+                )
+                .build())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    transact_chunked(&client, deletes).await?;
 
     Ok(())
 }
@@ -94,7 +90,7 @@ pub async fn add_test_item<S: Into<String>>(
     let req = build_request(
         "PUT",
         "/v1/seller/item",
-        &user_info.token,
+        user_info.token.as_deref().expect("caller is logged in, so a token is always present"),
         Some(add_item_req),
     )?;
     let resp = service.oneshot(req).await?;
@@ -108,28 +104,33 @@ pub async fn add_test_item<S: Into<String>>(
     Ok(item_ref)
 }
 
+/// Log in the fixture seller, registering it on first use.
+///
+/// OPAQUE envelopes can't be hand-rolled into seed data, so unlike the old scrypt-based
+/// login this no longer assumes the fixture account is pre-seeded in DynamoDB.
 pub async fn test_seller_login(state: Arc<AppState>) -> Result<UserInfo, Error> {
-    let service = create_service(state.clone()).await?;
-    let login_payload = LoginPayload {
-        email: TEST_SELLER_EMAIL.to_string(),
-        user_type: UserType::Seller,
-        password: TEST_SELLER_PASSWORD.to_string(),
-    };
-    let payload: String = serde_json::to_string(&login_payload)?;
-
-    let req = Request::builder()
-        .method("POST")
-        .header("Content-Type", "application/json")
-        .uri("/v1/login")
-        .body(payload)?;
-
-    let resp = service.oneshot(req).await?;
-
-    assert_eq!(resp.status(), StatusCode::OK);
-
-    let user_info: UserInfo = parse_resp(resp).await?;
-
-    Ok(user_info)
+    match opaque_login(
+        state.clone(),
+        TEST_SELLER_EMAIL,
+        UserType::Seller,
+        TEST_SELLER_PASSWORD,
+    )
+    .await
+    {
+        Ok(user_info) => Ok(user_info),
+        Err(_) => {
+            opaque_register(
+                state.clone(),
+                "Foo",
+                "Seller",
+                TEST_SELLER_EMAIL,
+                UserType::Seller,
+                TEST_SELLER_PASSWORD,
+            )
+            .await?;
+            opaque_login(state, TEST_SELLER_EMAIL, UserType::Seller, TEST_SELLER_PASSWORD).await
+        }
+    }
 }
 
 #[tokio::test]
@@ -155,7 +156,12 @@ async fn test_seller_get_items() -> Result<(), Error> {
     }
     let service = create_service(state.clone()).await?;
 
-    let req = build_request::<()>("GET", "/v1/seller/item", &user_info.token, None)?;
+    let req = build_request::<()>(
+        "GET",
+        "/v1/seller/item",
+        user_info.token.as_deref().expect("caller is logged in, so a token is always present"),
+        None,
+    )?;
     let resp = service.oneshot(req).await?;
 
     let resp_items: Vec<Item> = parse_resp(resp).await?;
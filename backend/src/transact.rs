@@ -0,0 +1,72 @@
+//! Helpers for issuing DynamoDB transactions that may exceed the 25-action
+//! limit of a single `transact_write_items` call.
+
+use aws_sdk_dynamodb::{
+    operation::transact_write_items::{TransactWriteItemsError, TransactWriteItemsOutput},
+    types::TransactWriteItem,
+    Client,
+};
+
+use crate::errors::HandlerError;
+
+/// Max actions DynamoDB accepts in a single `transact_write_items` call.
+const TRANSACT_WRITE_CHUNK_SIZE: usize = 25;
+
+/// Splits a `Vec<TransactWriteItem>` into chunks that each respect
+/// DynamoDB's 25-action-per-transaction limit.
+pub trait IntoChunks {
+    fn into_chunks(self) -> std::vec::IntoIter<Vec<TransactWriteItem>>;
+}
+
+impl IntoChunks for Vec<TransactWriteItem> {
+    fn into_chunks(self) -> std::vec::IntoIter<Vec<TransactWriteItem>> {
+        self.chunks(TRANSACT_WRITE_CHUNK_SIZE)
+            .map(|chunk| chunk.to_vec())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// Issue `items` as one or more `transact_write_items` calls, each holding at
+/// most 25 actions, and return every chunk's output in order.
+///
+/// DynamoDB transactions aren't atomic across chunks, so a failure midway
+/// leaves earlier chunks committed; the returned error carries the index of
+/// the chunk that failed so callers can reason about the partial commit.
+pub async fn transact_chunked(
+    client: &Client,
+    items: Vec<TransactWriteItem>,
+) -> Result<Vec<TransactWriteItemsOutput>, HandlerError> {
+    let mut outputs = Vec::new();
+    for (chunk_index, chunk) in items.into_chunks().enumerate() {
+        if chunk.is_empty() {
+            continue;
+        }
+        let output = client
+            .transact_write_items()
+            .set_transact_items(Some(chunk))
+            .send()
+            .await
+            .map_err(|source| HandlerError::TransactChunkError {
+                chunk_index,
+                source,
+            })?;
+        outputs.push(output);
+    }
+    Ok(outputs)
+}
+
+/// Whether `err` is a DynamoDB `TransactionCanceledException`, i.e. one of the
+/// transaction's condition expressions failed. Callers that condition a
+/// transaction on optimistic-concurrency state (e.g. `buyer_place_bid`'s
+/// `bidVersion` check) use this to tell a stale-read retry apart from a real
+/// failure.
+pub fn is_transaction_canceled(err: &HandlerError) -> bool {
+    matches!(
+        err,
+        HandlerError::TransactChunkError { source, .. }
+            if source
+                .as_service_error()
+                .is_some_and(TransactWriteItemsError::is_transaction_canceled_exception)
+    )
+}
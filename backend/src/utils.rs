@@ -1,10 +1,14 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use axum::http::StatusCode;
 use base64::{prelude::BASE64_URL_SAFE, Engine};
 use sha3::{
     digest::{ExtendableOutput, Update, XofReader},
     Shake128,
 };
 
-use crate::models::user::UserType;
+use crate::{errors::HandlerError, models::user::UserType};
 
 pub fn create_userid(email: &str, user_type: UserType) -> String {
     let mut hasher = Shake128::default();
@@ -16,3 +20,46 @@ pub fn create_userid(email: &str, user_type: UserType) -> String {
     let suffix = BASE64_URL_SAFE.encode(&buf);
     format!("{}_{}", user_type.to_string(), suffix)
 }
+
+/// Encode a DynamoDB `LastEvaluatedKey` as an opaque pagination cursor.
+pub fn encode_cursor(key: HashMap<String, AttributeValue>) -> Result<String, HandlerError> {
+    let json: serde_json::Value = serde_dynamo::from_item(key)?;
+    let bytes = serde_json::to_vec(&json)?;
+    Ok(BASE64_URL_SAFE.encode(bytes))
+}
+
+/// Decode an opaque pagination cursor back into an `ExclusiveStartKey`.
+pub fn decode_cursor(cursor: &str) -> Result<HashMap<String, AttributeValue>, HandlerError> {
+    let bytes = BASE64_URL_SAFE.decode(cursor).map_err(|e| {
+        HandlerError::HandlerError(StatusCode::BAD_REQUEST, format!("Invalid cursor: {}", e))
+    })?;
+    let json: serde_json::Value = serde_json::from_slice(&bytes)?;
+    Ok(serde_dynamo::to_item(json)?)
+}
+
+/// Lowercase hex-encode bytes, used for OPAQUE protocol messages over JSON.
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decode a (optionally `0x`-prefixed) hex string back into bytes.
+pub fn hex_decode(hex: &str) -> Result<Vec<u8>, HandlerError> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.len() % 2 != 0 {
+        return Err(HandlerError::HandlerError(
+            StatusCode::BAD_REQUEST,
+            "Invalid hex string".to_string(),
+        ));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| {
+                HandlerError::HandlerError(
+                    StatusCode::BAD_REQUEST,
+                    "Invalid hex string".to_string(),
+                )
+            })
+        })
+        .collect()
+}